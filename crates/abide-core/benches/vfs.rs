@@ -0,0 +1,94 @@
+//! VFS operations on a project of 10,000 files spread over 100 directories.
+//! Run with `cargo bench -p abide-core`.
+
+use std::path::{Path, PathBuf};
+
+use abide_core::state::batch::BatchOperation;
+use abide_core::VirtualFileSystem;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+const DIRECTORIES: usize = 100;
+const FILES_PER_DIRECTORY: usize = 100;
+
+fn project() -> VirtualFileSystem {
+    let mut vfs = VirtualFileSystem::new();
+    for dir in 0..DIRECTORIES {
+        for file in 0..FILES_PER_DIRECTORY {
+            let path = format!("/src/module_{dir}/file_{file}.rs");
+            vfs.create_file_all(Path::new(&path), String::new()).unwrap();
+        }
+    }
+    vfs
+}
+
+fn last_file() -> PathBuf {
+    PathBuf::from(format!("/src/module_{}/file_{}.rs", DIRECTORIES - 1, FILES_PER_DIRECTORY - 1))
+}
+
+fn lookups(c: &mut Criterion) {
+    let vfs = project();
+    let path = last_file();
+    let file_id = vfs.resolve_file(path.to_str().unwrap()).unwrap();
+
+    c.bench_function("resolve_file by path", |b| {
+        b.iter(|| vfs.file_id_at(black_box(&path)).unwrap())
+    });
+    c.bench_function("path_of", |b| b.iter(|| vfs.path_of(black_box(&file_id)).unwrap()));
+    c.bench_function("list_directory of one module", |b| {
+        b.iter(|| vfs.list_directory(black_box(Path::new("/src/module_50"))).unwrap())
+    });
+    c.bench_function("list_directory of the top level", |b| {
+        b.iter(|| vfs.list_directory(black_box(Path::new("/src"))).unwrap())
+    });
+    c.bench_function("tree of the whole project", |b| b.iter(|| vfs.tree(black_box(Path::new("/"))).unwrap()));
+}
+
+fn mutations(c: &mut Criterion) {
+    c.bench_function("create and delete file", |b| {
+        b.iter_batched_ref(
+            project,
+            |vfs| {
+                let file_id = vfs.create_file(Path::new("/src/module_50/new.rs"), String::new()).unwrap();
+                vfs.delete_file(&file_id).unwrap();
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    c.bench_function("delete last file", |b| {
+        b.iter_batched_ref(
+            project,
+            |vfs| {
+                let file_id = vfs.resolve_file(last_file().to_str().unwrap()).unwrap();
+                vfs.delete_file(&file_id).unwrap();
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    c.bench_function("move directory of 100 files", |b| {
+        b.iter_batched_ref(
+            project,
+            |vfs| vfs.move_node(Path::new("/src/module_50"), Path::new("/moved")).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+    c.bench_function("snapshot whole project", |b| {
+        b.iter_batched_ref(
+            project,
+            |vfs| vfs.create_snapshot("bench").unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+    let update: Vec<BatchOperation> = serde_json::from_value(serde_json::json!([
+        { "op": "update", "path": last_file(), "content": "fn updated() {}" },
+    ])).unwrap();
+    c.bench_function("batch of one update", |b| {
+        b.iter_batched_ref(
+            project,
+            |vfs| vfs.apply_batch(black_box(&update)).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, lookups, mutations);
+criterion_main!(benches);
@@ -0,0 +1,639 @@
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+};
+use std::sync::Arc;
+use serde::{Serialize, Deserialize};
+use tracing::{info, error};
+
+use super::extract::{Json, Query};
+use crate::error::Error;
+use crate::state::batch::BatchOperation;
+use crate::state::diff::{DiffOptions, DiffSide};
+use crate::state::patch::DEFAULT_FUZZ;
+use crate::state::vfs::ConflictPolicy;
+use crate::state::workspace;
+use crate::state::{AppState, Author, EditorSelection, VirtualFileSystem};
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateFileRequest {
+    pub path: String,
+    pub content: String,
+    #[serde(default)]
+    pub parents: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UpdateFileRequest {
+    pub content: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateDirectoryRequest {
+    pub path: String,
+    #[serde(default)]
+    pub parents: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeleteDirectoryQuery {
+    #[serde(default)]
+    pub recursive: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RenameRequest {
+    pub path: String,
+    pub new_name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MoveRequest {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CopyRequest {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub on_conflict: ConflictPolicy,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PatchRequest {
+    pub patch: String,
+    pub fuzz: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DiffRequest {
+    pub from: DiffSide,
+    pub to: DiffSide,
+    #[serde(flatten)]
+    pub options: DiffOptions,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BatchRequest {
+    pub operations: Vec<BatchOperation>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateSnapshotRequest {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RevisionQuery {
+    /// Read this revision instead of listing them all
+    pub version: Option<u64>,
+}
+
+/// Moves a file through its history: `{"action": "undo"}`,
+/// `{"action": "redo"}` or `{"action": "revert", "version": n}`.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum HistoryRequest {
+    Undo,
+    Redo,
+    Revert { version: u64 },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FileResponse {
+    pub id: String,
+    pub name: String,
+    pub content: String,
+    pub language: Option<String>,
+    pub version: u64,
+}
+
+/// Body of every failed REST call: the message plus the error's `kind` and
+/// variant details, e.g. `{"error": "...", "kind": "not_found", "target": "..."}`.
+#[derive(Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub kind: String,
+    #[serde(flatten)]
+    pub details: serde_json::Map<String, serde_json::Value>,
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let body = ErrorResponse {
+            error: self.to_string(),
+            kind: self.kind().to_string(),
+            details: self.details(),
+        };
+        (self.http_status(), Json(body)).into_response()
+    }
+}
+
+// File handlers
+pub async fn create_file_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CreateFileRequest>,
+) -> impl IntoResponse {
+    let mut vfs = state.vfs_as(&Author::api()).await;
+    let path = std::path::Path::new(&payload.path);
+    let created = if payload.parents {
+        vfs.create_file_all(path, payload.content)
+    } else {
+        vfs.create_file(path, payload.content)
+    };
+    
+    match created {
+        Ok(file_id) => {
+            info!("Created file: {} with ID: {}", payload.path, file_id);
+            (StatusCode::CREATED, Json(serde_json::json!({ "id": file_id }))).into_response()
+        }
+        Err(e) => {
+            error!("Failed to create file: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+pub async fn read_file_handler(
+    State(state): State<Arc<AppState>>,
+    Path(file): Path<String>,
+) -> Response {
+    let vfs = state.vfs.read().await;
+    
+    match resolve_file(&vfs, &file).and_then(|file_id| vfs.read_file(&file_id)) {
+        Ok(file) => {
+            let response = FileResponse {
+                id: file.id.clone(),
+                name: file.name.clone(),
+                content: file.content.to_string(),
+                language: file.language.clone(),
+                version: file.version,
+            };
+            (StatusCode::OK, [(header::ETAG, etag(file.version))], Json(response)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to read file: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+pub async fn update_file_handler(
+    State(state): State<Arc<AppState>>,
+    Path(file): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateFileRequest>,
+) -> impl IntoResponse {
+    let mut vfs = state.vfs_as(&Author::api()).await;
+    let updated = resolve_file(&vfs, &file).and_then(|file_id| {
+        check_if_match(&vfs, &file_id, &headers)?;
+        vfs.write_file(&file_id, payload.content)?;
+        Ok(vfs.read_file(&file_id)?.version)
+    });
+    
+    match updated {
+        Ok(version) => {
+            info!("Updated file: {}", file);
+            (StatusCode::OK, [(header::ETAG, etag(version))], Json(serde_json::json!({ "version": version })))
+                .into_response()
+        }
+        Err(e) => {
+            error!("Failed to update file: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+pub async fn delete_file_handler(
+    State(state): State<Arc<AppState>>,
+    Path(file): Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let mut vfs = state.vfs_as(&Author::api()).await;
+    let deleted = resolve_file(&vfs, &file).and_then(|file_id| {
+        check_if_match(&vfs, &file_id, &headers)?;
+        vfs.delete_file(&file_id)
+    });
+    
+    match deleted {
+        Ok(_) => {
+            info!("Deleted file: {}", file);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => {
+            error!("Failed to delete file: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+/// `/api/files/*file` carries either a file id or a path without its
+/// leading slash, e.g. `/api/files/src/main.js`.
+fn resolve_file(vfs: &VirtualFileSystem, file: &str) -> crate::error::Result<String> {
+    vfs.resolve_file(file)
+        .or_else(|_| vfs.resolve_file(&format!("/{}", file.trim_start_matches('/'))))
+}
+
+// History handlers
+pub async fn file_history_handler(
+    State(state): State<Arc<AppState>>,
+    Path(file): Path<String>,
+    Query(query): Query<RevisionQuery>,
+) -> Response {
+    let vfs = state.vfs.read().await;
+    let file_id = match resolve_file(&vfs, &file) {
+        Ok(file_id) => file_id,
+        Err(e) => return e.into_response(),
+    };
+    
+    let result = match query.version {
+        Some(version) => vfs.revision(&file_id, version).map(|revision| Json(revision).into_response()),
+        None => vfs.history(&file_id).map(|history| Json(history).into_response()),
+    };
+    result.unwrap_or_else(|e| {
+        error!("Failed to read history of {}: {}", file, e);
+        e.into_response()
+    })
+}
+
+pub async fn update_history_handler(
+    State(state): State<Arc<AppState>>,
+    Path(file): Path<String>,
+    Json(payload): Json<HistoryRequest>,
+) -> Response {
+    let mut vfs = state.vfs_as(&Author::api()).await;
+    let moved = resolve_file(&vfs, &file).and_then(|file_id| match payload {
+        HistoryRequest::Undo => vfs.undo(&file_id),
+        HistoryRequest::Redo => vfs.redo(&file_id),
+        HistoryRequest::Revert { version } => vfs.revert(&file_id, version),
+    });
+    
+    match moved {
+        Ok(version) => {
+            info!("Moved {} through its history to version {}", file, version);
+            (StatusCode::OK, Json(serde_json::json!({ "version": version }))).into_response()
+        }
+        Err(e) => {
+            error!("Failed to move {} through its history: {}", file, e);
+            e.into_response()
+        }
+    }
+}
+
+/// A file version as an `ETag` value.
+fn etag(version: u64) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{}\"", version)).unwrap()
+}
+
+/// Checks an `If-Match` header, if there is one, against the file's
+/// version. `*` matches any version.
+fn check_if_match(vfs: &VirtualFileSystem, file_id: &str, headers: &HeaderMap) -> crate::error::Result<()> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(());
+    };
+    let value = value.to_str().unwrap_or_default().trim();
+    if value == "*" {
+        return Ok(());
+    }
+    
+    // Weak tags name the same versions, so `W/"3"` matches like `"3"`
+    let versions = value.split(',')
+        .map(|tag| {
+            let tag = tag.trim();
+            tag.strip_prefix("W/").unwrap_or(tag).strip_prefix('"')?.strip_suffix('"')?.parse::<u64>().ok()
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| Error::InvalidArgument(format!("If-Match is not a list of file versions: {}", value)))?;
+    let current = vfs.read_file(file_id)?.version;
+    if versions.contains(&current) {
+        return Ok(());
+    }
+    vfs.check_version(file_id, versions.first().copied())
+}
+
+// Directory handlers
+pub async fn create_directory_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CreateDirectoryRequest>,
+) -> impl IntoResponse {
+    let mut vfs = state.vfs_as(&Author::api()).await;
+    let path = std::path::Path::new(&payload.path);
+    let created = if payload.parents {
+        vfs.create_directory_all(path)
+    } else {
+        vfs.create_directory(path)
+    };
+    
+    match created {
+        Ok(dir_id) => {
+            info!("Created directory: {} with ID: {}", payload.path, dir_id);
+            (StatusCode::CREATED, Json(serde_json::json!({ "id": dir_id }))).into_response()
+        }
+        Err(e) => {
+            error!("Failed to create directory: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+pub async fn list_directory_handler(
+    State(state): State<Arc<AppState>>,
+    Path(path): Path<String>,
+) -> Response {
+    let vfs = state.vfs.read().await;
+    let path = if path.is_empty() { "/" } else { &path };
+    
+    match vfs.list_directory(std::path::Path::new(path)) {
+        Ok(nodes) => {
+            info!("Listed directory: {}", path);
+            (StatusCode::OK, Json(nodes)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to list directory: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+pub async fn tree_handler(
+    State(state): State<Arc<AppState>>,
+    Path(path): Path<String>,
+) -> Response {
+    let vfs = state.vfs.read().await;
+    let path = if path.is_empty() { "/" } else { &path };
+    
+    match vfs.tree(std::path::Path::new(path)) {
+        Ok(tree) => (StatusCode::OK, Json(tree)).into_response(),
+        Err(e) => {
+            error!("Failed to build tree: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+pub async fn delete_directory_handler(
+    State(state): State<Arc<AppState>>,
+    Path(path): Path<String>,
+    Query(query): Query<DeleteDirectoryQuery>,
+) -> Response {
+    let mut vfs = state.vfs_as(&Author::api()).await;
+    let path = format!("/{}", path.trim_start_matches('/'));
+    
+    match vfs.delete_directory(std::path::Path::new(&path), query.recursive) {
+        Ok(report) => {
+            info!("Deleted directory: {} ({} files)", path, report.removed_files.len());
+            (StatusCode::OK, Json(report)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to delete directory: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+// Node handlers (files and directories)
+pub async fn rename_node_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<RenameRequest>,
+) -> Response {
+    let mut vfs = state.vfs_as(&Author::api()).await;
+    
+    match vfs.rename(std::path::Path::new(&payload.path), &payload.new_name) {
+        Ok(node) => {
+            info!("Renamed {} to {}", payload.path, node.path.display());
+            (StatusCode::OK, Json(node)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to rename {}: {}", payload.path, e);
+            e.into_response()
+        }
+    }
+}
+
+pub async fn move_node_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<MoveRequest>,
+) -> Response {
+    let mut vfs = state.vfs_as(&Author::api()).await;
+    
+    match vfs.move_node(std::path::Path::new(&payload.from), std::path::Path::new(&payload.to)) {
+        Ok(node) => {
+            info!("Moved {} to {}", payload.from, payload.to);
+            (StatusCode::OK, Json(node)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to move {}: {}", payload.from, e);
+            e.into_response()
+        }
+    }
+}
+
+pub async fn copy_node_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CopyRequest>,
+) -> Response {
+    let mut vfs = state.vfs_as(&Author::api()).await;
+    
+    match vfs.copy_node(std::path::Path::new(&payload.from), std::path::Path::new(&payload.to), payload.on_conflict) {
+        Ok(report) => {
+            info!("Copied {} to {} ({} created)", payload.from, payload.to, report.created.len());
+            (StatusCode::OK, Json(report)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to copy {}: {}", payload.from, e);
+            e.into_response()
+        }
+    }
+}
+
+pub async fn apply_patch_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<PatchRequest>,
+) -> Response {
+    let mut vfs = state.vfs_as(&Author::api()).await;
+    
+    match vfs.apply_patch(&payload.patch, payload.fuzz.unwrap_or(DEFAULT_FUZZ)) {
+        Ok(report) => {
+            info!("Applied patch to {} files", report.files.len());
+            (StatusCode::OK, Json(report)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to apply patch: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+pub async fn diff_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<DiffRequest>,
+) -> Response {
+    let vfs = state.vfs.read().await;
+    
+    match vfs.diff(&payload.from, &payload.to, &payload.options) {
+        Ok(diff) => (StatusCode::OK, Json(diff)).into_response(),
+        Err(e) => {
+            error!("Failed to diff {} and {}: {}", payload.from.file, payload.to.file, e);
+            e.into_response()
+        }
+    }
+}
+
+pub async fn batch_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<BatchRequest>,
+) -> Response {
+    let mut vfs = state.vfs_as(&Author::api()).await;
+    
+    match vfs.apply_batch(&payload.operations) {
+        Ok(report) => {
+            info!("Applied batch of {} operations", payload.operations.len());
+            (StatusCode::OK, Json(report)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to apply batch: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+// Snapshot handlers
+pub async fn list_snapshots_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let vfs = state.vfs.read().await;
+    (StatusCode::OK, Json(serde_json::json!({ "snapshots": vfs.snapshots() })))
+}
+
+pub async fn create_snapshot_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CreateSnapshotRequest>,
+) -> Response {
+    let mut vfs = state.vfs_as(&Author::api()).await;
+    
+    match vfs.create_snapshot(&payload.name) {
+        Ok(info) => {
+            info!("Created snapshot: {}", payload.name);
+            (StatusCode::CREATED, Json(info)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to create snapshot {}: {}", payload.name, e);
+            e.into_response()
+        }
+    }
+}
+
+pub async fn diff_snapshot_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Query(options): Query<DiffOptions>,
+) -> Response {
+    let vfs = state.vfs.read().await;
+    
+    match vfs.diff_snapshot(&name, &options) {
+        Ok(diff) => (StatusCode::OK, Json(diff)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn restore_snapshot_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Response {
+    let mut vfs = state.vfs_as(&Author::api()).await;
+    
+    match vfs.restore_snapshot(&name) {
+        Ok(report) => {
+            info!("Restored snapshot: {}", name);
+            (StatusCode::OK, Json(report)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to restore snapshot {}: {}", name, e);
+            e.into_response()
+        }
+    }
+}
+
+pub async fn delete_snapshot_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Response {
+    let mut vfs = state.vfs_as(&Author::api()).await;
+    
+    match vfs.delete_snapshot(&name) {
+        Ok(_) => {
+            info!("Deleted snapshot: {}", name);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+// Project handlers
+pub async fn get_project_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let _vfs = state.vfs.read().await;
+    // For now, return the entire file tree
+    (StatusCode::OK, Json(serde_json::json!({ "root": "/" })))
+}
+
+pub async fn save_project_handler(
+    State(state): State<Arc<AppState>>,
+) -> Response {
+    match workspace::save_state(&state).await {
+        Ok(report) => {
+            info!("Saved workspace to {}", report.path.display());
+            (StatusCode::OK, Json(report)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to save workspace: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+// Settings handlers
+pub async fn get_settings_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let config = state.config.read().await;
+    (StatusCode::OK, Json(config.clone()))
+}
+
+pub async fn update_settings_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<serde_json::Value>,
+) -> Response {
+    match state.update_settings(&payload).await {
+        Ok(config) => {
+            info!("Updated settings");
+            (StatusCode::OK, Json(config)).into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+// Tool handlers
+pub async fn list_tools_handler() -> impl IntoResponse {
+    (StatusCode::OK, Json(serde_json::json!({ "tools": crate::mcp::tools::list_tools() })))
+}
+
+// Editor handlers
+pub async fn get_selection_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let selection = state.selection.read().await;
+    (StatusCode::OK, Json(selection.clone()))
+}
+
+pub async fn update_selection_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<Option<EditorSelection>>,
+) -> impl IntoResponse {
+    *state.selection.write().await = payload;
+    StatusCode::OK
+}
@@ -0,0 +1,142 @@
+use axum::{
+    Router,
+    routing::{get, post},
+    extract::{State, WebSocketUpgrade},
+    http::HeaderMap,
+    response::Response,
+};
+use tower_http::cors::{CorsLayer, Any};
+use std::future::Future;
+use std::sync::Arc;
+use tracing::info;
+
+mod extract;
+pub mod handlers;
+mod routes;
+pub mod streamable_http;
+mod websocket;
+
+use crate::state::AppState;
+use handlers::*;
+use websocket::handle_websocket;
+
+pub fn router(app_state: AppState) -> Router {
+    let app_state = Arc::new(app_state);
+    
+    Router::new()
+        // Health check
+        .route("/health", get(health_check))
+        
+        // MCP over WebSocket (GET upgrade) and Streamable HTTP
+        .route(
+            "/mcp",
+            get(mcp_get)
+                .post(streamable_http::handle_post)
+                .delete(streamable_http::handle_delete),
+        )
+        
+        // File operations
+        .route("/api/files", post(create_file_handler))
+        .route("/api/files/*file", get(read_file_handler))
+        .route("/api/files/*file", post(update_file_handler))
+        .route("/api/files/*file", axum::routing::delete(delete_file_handler))
+        .route("/api/history/*file", get(file_history_handler))
+        .route("/api/history/*file", post(update_history_handler))
+        
+        // Directory operations
+        .route("/api/directories", post(create_directory_handler))
+        .route("/api/directories/*path", get(list_directory_handler))
+        .route("/api/directories/*path", axum::routing::delete(delete_directory_handler))
+        .route("/api/tree/*path", get(tree_handler))
+        
+        // Operations on files and directories alike
+        .route("/api/nodes/rename", post(rename_node_handler))
+        .route("/api/nodes/move", post(move_node_handler))
+        .route("/api/nodes/copy", post(copy_node_handler))
+        .route("/api/patch", post(apply_patch_handler))
+        .route("/api/diff", post(diff_handler))
+        .route("/api/batch", post(batch_handler))
+        
+        // Workspace snapshots
+        .route("/api/snapshots", get(list_snapshots_handler))
+        .route("/api/snapshots", post(create_snapshot_handler))
+        .route("/api/snapshots/:name", axum::routing::delete(delete_snapshot_handler))
+        .route("/api/snapshots/:name/diff", get(diff_snapshot_handler))
+        .route("/api/snapshots/:name/restore", post(restore_snapshot_handler))
+        
+        // Project operations
+        .route("/api/project", get(get_project_handler))
+        .route("/api/project", post(save_project_handler))
+        
+        // Settings
+        .route("/api/settings", get(get_settings_handler))
+        .route("/api/settings", post(update_settings_handler))
+        
+        // Editor selection, used to fill MCP prompts
+        .route("/api/editor/selection", get(get_selection_handler))
+        .route("/api/editor/selection", post(update_selection_handler))
+        
+        // MCP tool definitions with their input schemas
+        .route("/api/tools", get(list_tools_handler))
+        
+        // Add state
+        .with_state(app_state.clone())
+        
+        // Add CORS support
+        .layer(
+            CorsLayer::new()
+                .allow_origin(Any)
+                .allow_methods(Any)
+                .allow_headers(Any)
+                .expose_headers(Any),
+        )
+}
+
+pub async fn start_server(app_state: AppState) -> anyhow::Result<()> {
+    start_server_with_shutdown(app_state, std::future::pending()).await
+}
+
+/// Serves the API on `ApiConfig.host`/`ApiConfig.port` until `shutdown`
+/// resolves, then lets in-flight requests finish. Open SSE streams are
+/// ended rather than waited on. Abandoned MCP HTTP sessions expire after
+/// `ApiConfig.timeout` seconds.
+pub async fn start_server_with_shutdown(
+    app_state: AppState,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<()> {
+    let (host, port) = {
+        let config = app_state.config.read().await;
+        (config.api.host.clone(), config.api.port)
+    };
+    let app = router(app_state.clone());
+    streamable_http::spawn_session_reaper(app_state.clone());
+    let shutdown = async move {
+        shutdown.await;
+        app_state.begin_shutdown();
+    };
+    
+    let listener = tokio::net::TcpListener::bind((host.as_str(), port)).await?;
+    info!("API server listening on {}", listener.local_addr()?);
+    
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown)
+        .await?;
+    
+    info!("API server stopped");
+    Ok(())
+}
+
+async fn health_check() -> &'static str {
+    "OK"
+}
+
+async fn mcp_get(
+    ws: Option<WebSocketUpgrade>,
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Response {
+    match ws {
+        Some(ws) => ws.on_upgrade(move |socket| handle_websocket(socket, state)),
+        None => streamable_http::handle_get(state, headers).await,
+    }
+}
\ No newline at end of file
@@ -0,0 +1,173 @@
+//! MCP Streamable HTTP transport: JSON-RPC over `POST /mcp`, with responses
+//! returned as JSON or a short SSE stream, and a long-lived SSE stream on
+//! `GET /mcp` for server-initiated messages. Sessions are the same entries
+//! in `AppState::sessions` that the WebSocket and stdio transports use.
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+};
+use futures::{stream, StreamExt};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::mcp;
+use crate::state::AppState;
+
+pub const SESSION_HEADER: &str = "mcp-session-id";
+pub const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+
+/// Shortest idle time before a session is dropped, whatever `api.timeout` says.
+const MIN_SESSION_IDLE: Duration = Duration::from_secs(1);
+
+pub async fn handle_post(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    if !protocol_version_supported(&headers) {
+        return (StatusCode::BAD_REQUEST, "Unsupported MCP protocol version").into_response();
+    }
+
+    let (session_id, new_session) = match session_header(&headers) {
+        Some(session_id) if state.sessions.contains_key(&session_id) => (session_id, false),
+        Some(_) => return (StatusCode::NOT_FOUND, "Unknown MCP session").into_response(),
+        None if is_initialize(&body) => (state.create_session(), true),
+        None => return (StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header").into_response(),
+    };
+
+    state.update_session_activity(&session_id);
+    let message = mcp::handle_message(&body, &session_id, &state).await;
+
+    if new_session {
+        let negotiated = state.sessions.get(&session_id)
+            .is_some_and(|session| session.protocol_version.is_some());
+        if negotiated {
+            info!("New MCP HTTP session: {}", session_id);
+        } else {
+            // Failed initialize; don't keep a session the client never learns about.
+            state.sessions.remove(&session_id);
+        }
+    }
+
+    let mut response = match message {
+        None => StatusCode::ACCEPTED.into_response(),
+        Some(message) if accepts_event_stream(&headers) => {
+            let event = Ok::<_, Infallible>(Event::default().data(message));
+            Sse::new(stream::once(async move { event })).into_response()
+        }
+        Some(message) => ([(header::CONTENT_TYPE, "application/json")], message).into_response(),
+    };
+
+    if state.sessions.contains_key(&session_id) {
+        response.headers_mut().insert(SESSION_HEADER, HeaderValue::from_str(&session_id).unwrap());
+    }
+    response
+}
+
+/// Opens the standalone SSE stream for server-initiated messages.
+pub async fn handle_get(state: Arc<AppState>, headers: HeaderMap) -> Response {
+    if !accepts_event_stream(&headers) {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    }
+    if !protocol_version_supported(&headers) {
+        return (StatusCode::BAD_REQUEST, "Unsupported MCP protocol version").into_response();
+    }
+
+    let Some(session_id) = session_header(&headers) else {
+        return (StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header").into_response();
+    };
+    let Some(receiver) = state.subscribe_session(&session_id) else {
+        return (StatusCode::NOT_FOUND, "Unknown MCP session").into_response();
+    };
+
+    // Ends when the session is deleted and its sender dropped.
+    let events = stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(message) => return Some((Ok::<_, Infallible>(Event::default().data(message)), receiver)),
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("MCP SSE stream dropped {} messages", skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    // Or when the server shuts down, which would otherwise wait on it
+    let events = events.take_until(state.shutting_down());
+
+    Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+}
+
+pub async fn handle_delete(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> StatusCode {
+    let Some(session_id) = session_header(&headers) else {
+        return StatusCode::BAD_REQUEST;
+    };
+
+    match state.sessions.remove(&session_id) {
+        Some(_) => {
+            info!("MCP HTTP session terminated: {}", session_id);
+            StatusCode::NO_CONTENT
+        }
+        None => StatusCode::NOT_FOUND,
+    }
+}
+
+/// Drops sessions idle for `api.timeout` seconds with no GET stream open,
+/// since clients that just go away never send DELETE. The timeout is re-read
+/// each round; the task ends when the server shuts down.
+pub fn spawn_session_reaper(state: AppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let stopped = state.shutting_down();
+        tokio::pin!(stopped);
+        loop {
+            let timeout = state.config.read().await.api.timeout;
+            let idle = Duration::from_secs(timeout.into()).max(MIN_SESSION_IDLE);
+            tokio::select! {
+                _ = tokio::time::sleep(idle) => {}
+                _ = &mut stopped => return,
+            }
+            for session_id in state.evict_idle_sessions(idle) {
+                info!("MCP HTTP session expired: {}", session_id);
+            }
+        }
+    })
+}
+
+fn session_header(headers: &HeaderMap) -> Option<String> {
+    headers.get(SESSION_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+fn accepts_event_stream(headers: &HeaderMap) -> bool {
+    headers.get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .any(|value| value.contains("text/event-stream"))
+}
+
+/// Clients that omit the header are assumed to speak a version we support.
+fn protocol_version_supported(headers: &HeaderMap) -> bool {
+    match headers.get(PROTOCOL_VERSION_HEADER).map(|value| value.to_str()) {
+        None => true,
+        Some(Ok(version)) => mcp::SUPPORTED_PROTOCOL_VERSIONS.contains(&version),
+        Some(Err(_)) => false,
+    }
+}
+
+fn is_initialize(body: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(body)
+        .is_ok_and(|message| message.get("method").and_then(|m| m.as_str()) == Some("initialize"))
+}
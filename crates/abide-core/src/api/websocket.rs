@@ -0,0 +1,61 @@
+use axum::extract::ws::{WebSocket, Message};
+use futures::{sink::SinkExt, stream::StreamExt};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, error, debug, warn};
+
+use crate::mcp;
+use crate::state::AppState;
+
+pub async fn handle_websocket(socket: WebSocket, state: Arc<AppState>) {
+    let session_id = state.create_session();
+    info!("New MCP WebSocket connection established: {}", session_id);
+    
+    let (mut sender, mut receiver) = socket.split();
+    let Some(mut outbound) = state.subscribe_session(&session_id) else {
+        return;
+    };
+    
+    loop {
+        tokio::select! {
+            msg = receiver.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    state.update_session_activity(&session_id);
+                    debug!("Received MCP message: {}", text);
+                    
+                    if let Some(response) = mcp::handle_message(&text, &session_id, &state).await {
+                        if let Err(e) = sender.send(Message::Text(response)).await {
+                            error!("Failed to send response: {}", e);
+                            break;
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    info!("WebSocket connection closed by client: {}", session_id);
+                    break;
+                }
+                Some(Err(e)) => {
+                    error!("WebSocket error: {}", e);
+                    break;
+                }
+                Some(Ok(_)) => {}
+            },
+            // Server-initiated notifications, e.g. resource updates
+            message = outbound.recv() => match message {
+                Ok(message) => {
+                    if let Err(e) = sender.send(Message::Text(message)).await {
+                        error!("Failed to send notification: {}", e);
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("MCP WebSocket dropped {} notifications", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+    
+    state.sessions.remove(&session_id);
+    info!("MCP WebSocket connection terminated: {}", session_id);
+}
\ No newline at end of file
@@ -0,0 +1,166 @@
+//! The error type shared by the VFS and every API surface. Each variant has
+//! a stable `kind` string, an HTTP status and a JSON-RPC code, so REST, MCP
+//! and Tauri clients can all branch on the same thing.
+
+use axum::http::StatusCode;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+use crate::state::batch::BatchReport;
+use crate::state::patch::PatchReport;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// No file or directory with this id or path
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("Already exists: {}", .0.display())]
+    AlreadyExists(PathBuf),
+    #[error("Not a directory: {}", .0.display())]
+    NotADirectory(PathBuf),
+    #[error("Is a directory: {}", .0.display())]
+    IsADirectory(PathBuf),
+    #[error("Directory not empty: {}", .0.display())]
+    DirectoryNotEmpty(PathBuf),
+    #[error("Invalid path {path}: {reason}")]
+    InvalidPath { path: String, reason: String },
+    /// The request is valid but clashes with the current state
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    /// The file is no longer at the version the caller based its change on
+    #[error("Version conflict on {file}: expected version {expected}, but it is at {current}")]
+    VersionConflict {
+        file: String,
+        expected: u64,
+        current: u64,
+        /// The file's content at `current`, so the caller can merge
+        content: String,
+    },
+    /// Some part of a patch did not apply, so none of it was
+    #[error("Patch rejected, nothing was applied:\n{}", .0.summary())]
+    PatchRejected(Box<PatchReport>),
+    /// An operation in a batch failed, so none of them were kept
+    #[error("Batch failed, nothing was applied: {}", .0.summary())]
+    BatchFailed(Box<BatchReport>),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl Error {
+    pub fn not_found(target: impl AsRef<Path>) -> Self {
+        Self::NotFound(target.as_ref().display().to_string())
+    }
+
+    pub fn invalid_path(path: impl AsRef<Path>, reason: impl Into<String>) -> Self {
+        Self::InvalidPath {
+            path: path.as_ref().display().to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Stable, machine-readable name of the variant.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) => "not_found",
+            Self::AlreadyExists(_) => "already_exists",
+            Self::NotADirectory(_) => "not_a_directory",
+            Self::IsADirectory(_) => "is_a_directory",
+            Self::DirectoryNotEmpty(_) => "directory_not_empty",
+            Self::InvalidPath { .. } => "invalid_path",
+            Self::Conflict(_) => "conflict",
+            Self::VersionConflict { .. } => "version_conflict",
+            Self::PatchRejected(_) => "patch_rejected",
+            Self::BatchFailed(_) => "batch_failed",
+            Self::InvalidArgument(_) => "invalid_argument",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    pub fn http_status(&self) -> StatusCode {
+        match self {
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::AlreadyExists(_)
+            | Self::DirectoryNotEmpty(_)
+            | Self::Conflict(_)
+            | Self::VersionConflict { .. }
+            | Self::PatchRejected(_)
+            | Self::BatchFailed(_) => StatusCode::CONFLICT,
+            Self::NotADirectory(_) | Self::IsADirectory(_) | Self::InvalidPath { .. } | Self::InvalidArgument(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// JSON-RPC error code, distinct for each kind and from the protocol's
+    /// own server errors. Invalid arguments and internal errors use the
+    /// standard codes; the rest sit in the implementation-defined range.
+    pub fn jsonrpc_code(&self) -> i64 {
+        match self {
+            Self::NotFound(_) => -32001,
+            Self::AlreadyExists(_) => -32010,
+            Self::NotADirectory(_) => -32011,
+            Self::IsADirectory(_) => -32012,
+            Self::DirectoryNotEmpty(_) => -32013,
+            Self::InvalidPath { .. } => -32014,
+            Self::Conflict(_) => -32020,
+            Self::VersionConflict { .. } => -32023,
+            Self::PatchRejected(_) => -32021,
+            Self::BatchFailed(_) => -32022,
+            Self::InvalidArgument(_) => -32602,
+            Self::Internal(_) => -32603,
+        }
+    }
+
+    /// Variant-specific fields, e.g. `{"path": "/src"}`.
+    pub fn details(&self) -> Map<String, Value> {
+        let details = match self {
+            Self::NotFound(target) => json!({ "target": target }),
+            Self::AlreadyExists(path)
+            | Self::NotADirectory(path)
+            | Self::IsADirectory(path)
+            | Self::DirectoryNotEmpty(path) => json!({ "path": path }),
+            Self::InvalidPath { path, reason } => json!({ "path": path, "reason": reason }),
+            Self::PatchRejected(report) => json!({ "report": report }),
+            Self::BatchFailed(report) => json!({ "report": report }),
+            Self::VersionConflict { file, expected, current, content } => json!({
+                "file": file,
+                "expected": expected,
+                "current": current,
+                "content": content,
+            }),
+            Self::Conflict(_) | Self::InvalidArgument(_) | Self::Internal(_) => json!({}),
+        };
+        match details {
+            Value::Object(details) => details,
+            _ => Map::new(),
+        }
+    }
+
+    /// `kind` plus `details`, as sent in JSON-RPC `error.data`.
+    pub fn data(&self) -> Value {
+        let mut data = self.details();
+        data.insert("kind".to_string(), Value::String(self.kind().to_string()));
+        Value::Object(data)
+    }
+}
+
+/// Serializes as `{"kind", "message", ...details}`; this is what Tauri
+/// commands reject with.
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let details = self.details();
+        let mut map = serializer.serialize_map(Some(details.len() + 2))?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        for (key, value) in &details {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
@@ -0,0 +1,12 @@
+//! Core of ABIDE: the virtual file system, application configuration and
+//! sessions, plus the HTTP/MCP API that drives them. The Tauri desktop app
+//! and the headless `abide-server` are thin wrappers around this crate.
+
+pub mod api;
+pub mod error;
+pub mod mcp;
+pub mod state;
+pub mod utils;
+
+pub use error::Error;
+pub use state::{AppConfig, AppState, VirtualFileSystem};
@@ -0,0 +1,229 @@
+//! Model Context Protocol server: JSON-RPC 2.0 framing, lifecycle and
+//! capability negotiation, the tools backed by `McpRequest` operations, VFS
+//! files exposed as resources, and prompt templates.
+//! Transports hand raw messages to [`handle_message`] and send back whatever
+//! it returns.
+
+use std::sync::Arc;
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::{json, Value};
+use tracing::{debug, info};
+
+pub mod jsonrpc;
+pub mod operations;
+pub mod prompts;
+pub mod resources;
+pub mod stdio;
+pub mod tools;
+
+pub use operations::{handle_mcp_request, handle_mcp_request_as, McpRequest, McpResponse, Position};
+
+use crate::state::AppState;
+use jsonrpc::{
+    JsonRpcError, JsonRpcRequest, JsonRpcResponse, JSONRPC_VERSION, SERVER_NOT_INITIALIZED,
+};
+
+/// Protocol revisions this server speaks, newest first.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+pub const LATEST_PROTOCOL_VERSION: &str = SUPPORTED_PROTOCOL_VERSIONS[0];
+
+pub const SERVER_NAME: &str = "abide";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InitializeParams {
+    protocol_version: String,
+    #[serde(default)]
+    capabilities: Value,
+    #[serde(default)]
+    client_info: Option<Value>,
+}
+
+/// Handles one raw JSON-RPC message (or batch) received on `session_id`.
+/// Returns the serialized response, or `None` when nothing should be sent
+/// back (notifications, client responses).
+pub async fn handle_message(text: &str, session_id: &str, state: &Arc<AppState>) -> Option<String> {
+    let message: Value = match serde_json::from_str(text) {
+        Ok(message) => message,
+        Err(e) => {
+            let error = JsonRpcError::parse_error(format!("Parse error: {}", e));
+            return Some(to_json(&JsonRpcResponse::error(None, error)));
+        }
+    };
+
+    match message {
+        Value::Array(batch) => {
+            if batch.is_empty() {
+                let error = JsonRpcError::invalid_request("Empty batch");
+                return Some(to_json(&JsonRpcResponse::error(None, error)));
+            }
+
+            let mut responses = Vec::new();
+            for message in batch {
+                if let Some(response) = handle_value(message, session_id, state).await {
+                    responses.push(response);
+                }
+            }
+
+            (!responses.is_empty()).then(|| to_json(&responses))
+        }
+        message => handle_value(message, session_id, state)
+            .await
+            .map(|response| to_json(&response)),
+    }
+}
+
+async fn handle_value(message: Value, session_id: &str, state: &Arc<AppState>) -> Option<JsonRpcResponse> {
+    // Responses to server-initiated requests; we don't issue any yet.
+    if message.get("method").is_none()
+        && (message.get("result").is_some() || message.get("error").is_some())
+    {
+        debug!("Ignoring client response on session {}", session_id);
+        return None;
+    }
+
+    let id = message.get("id").and_then(|id| serde_json::from_value(id.clone()).ok());
+    let request: JsonRpcRequest = match serde_json::from_value(message) {
+        Ok(request) => request,
+        Err(e) => {
+            let error = JsonRpcError::invalid_request(format!("Invalid request: {}", e));
+            return Some(JsonRpcResponse::error(id, error));
+        }
+    };
+
+    if request.jsonrpc != JSONRPC_VERSION {
+        let error = JsonRpcError::invalid_request("jsonrpc must be \"2.0\"");
+        return request.id.map(|id| JsonRpcResponse::error(Some(id), error));
+    }
+
+    debug!("MCP {} on session {}", request.method, session_id);
+    let id = request.id.clone();
+    let result = dispatch(request, session_id, state).await;
+
+    // Notifications never get a response, not even an error.
+    let id = id?;
+    Some(match result {
+        Ok(result) => JsonRpcResponse::result(Some(id), result),
+        Err(error) => JsonRpcResponse::error(Some(id), error),
+    })
+}
+
+async fn dispatch(request: JsonRpcRequest, session_id: &str, state: &Arc<AppState>) -> Result<Value, JsonRpcError> {
+    match request.method.as_str() {
+        "initialize" => initialize(parse_params(request.params)?, session_id, state),
+        "notifications/initialized" => {
+            if let Some(mut session) = state.sessions.get_mut(session_id) {
+                session.initialized = true;
+            }
+            Ok(Value::Null)
+        }
+        "ping" => Ok(json!({})),
+        // Requests are handled to completion before the next is read, so
+        // there is never anything in flight to cancel.
+        "notifications/cancelled" => Ok(Value::Null),
+        "tools/list" => {
+            ensure_initialized(session_id, state)?;
+            Ok(json!({ "tools": tools::list_tools() }))
+        }
+        "tools/call" => {
+            ensure_initialized(session_id, state)?;
+            let result = tools::call_tool(parse_params(request.params)?, session_id, state).await?;
+            Ok(serde_json::to_value(result).unwrap())
+        }
+        "resources/list" => {
+            ensure_initialized(session_id, state)?;
+            Ok(json!({ "resources": resources::list_resources(state).await }))
+        }
+        "resources/templates/list" => {
+            ensure_initialized(session_id, state)?;
+            Ok(json!({ "resourceTemplates": resources::list_resource_templates() }))
+        }
+        "resources/read" => {
+            ensure_initialized(session_id, state)?;
+            let contents = resources::read_resource(parse_params(request.params)?, state).await?;
+            Ok(json!({ "contents": [contents] }))
+        }
+        "resources/subscribe" => {
+            ensure_initialized(session_id, state)?;
+            resources::subscribe(parse_params(request.params)?, session_id, state).await?;
+            Ok(json!({}))
+        }
+        "resources/unsubscribe" => {
+            ensure_initialized(session_id, state)?;
+            resources::unsubscribe(parse_params(request.params)?, session_id, state);
+            Ok(json!({}))
+        }
+        "prompts/list" => {
+            ensure_initialized(session_id, state)?;
+            Ok(json!({ "prompts": prompts::list_prompts(state).await }))
+        }
+        "prompts/get" => {
+            ensure_initialized(session_id, state)?;
+            prompts::get_prompt(parse_params(request.params)?, state).await
+        }
+        method => Err(JsonRpcError::method_not_found(method)),
+    }
+}
+
+fn ensure_initialized(session_id: &str, state: &Arc<AppState>) -> Result<(), JsonRpcError> {
+    let negotiated = state.sessions.get(session_id)
+        .is_some_and(|session| session.protocol_version.is_some());
+    if negotiated {
+        Ok(())
+    } else {
+        Err(JsonRpcError::new(SERVER_NOT_INITIALIZED, "Server not initialized"))
+    }
+}
+
+fn initialize(params: InitializeParams, session_id: &str, state: &Arc<AppState>) -> Result<Value, JsonRpcError> {
+    let protocol_version = negotiate_protocol_version(&params.protocol_version);
+
+    let mut session = state.sessions.get_mut(session_id)
+        .ok_or_else(|| JsonRpcError::internal_error(format!("Unknown session: {}", session_id)))?;
+    session.protocol_version = Some(protocol_version.to_string());
+    session.client_capabilities = params.capabilities;
+    session.client_info = params.client_info;
+
+    info!(
+        "MCP session {} initialized with protocol {} (client requested {})",
+        session_id, protocol_version, params.protocol_version
+    );
+
+    Ok(json!({
+        "protocolVersion": protocol_version,
+        "capabilities": server_capabilities(),
+        "serverInfo": {
+            "name": SERVER_NAME,
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "instructions": "ABIDE exposes a sandboxed virtual file system and editor animations. \
+            Use the file_* and directory_* tools to read and modify the workspace; \
+            files are also readable as abide://vfs/<path> resources.",
+    }))
+}
+
+/// Echoes the client's version when we support it; otherwise offers our
+/// latest and leaves it to the client to disconnect if it can't use it.
+pub fn negotiate_protocol_version(requested: &str) -> &'static str {
+    SUPPORTED_PROTOCOL_VERSIONS.iter()
+        .find(|version| **version == requested)
+        .copied()
+        .unwrap_or(LATEST_PROTOCOL_VERSION)
+}
+
+pub fn server_capabilities() -> Value {
+    json!({
+        "tools": { "listChanged": false },
+        "resources": { "subscribe": true, "listChanged": false },
+        "prompts": { "listChanged": false },
+    })
+}
+
+fn parse_params<T: DeserializeOwned>(params: Option<Value>) -> Result<T, JsonRpcError> {
+    serde_json::from_value(params.unwrap_or_else(|| json!({})))
+        .map_err(|e| JsonRpcError::invalid_params(format!("Invalid params: {}", e)))
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap()
+}
@@ -0,0 +1,203 @@
+//! MCP prompts: templates for common IDE workflows, filled in server-side
+//! from the VFS and the editor selection.
+//!
+//! Templates are JSON files with `name`, `title`, `description`, `arguments`
+//! and `template`. Besides its arguments, a template that takes a `path` can
+//! use `{{content}}`, `{{language}}` and `{{selection}}` from that file.
+//! `{{#arg}}...{{/arg}}` sections only render when `arg` is non-empty.
+//! Custom templates are read from `McpConfig::prompts_dir` on every request
+//! and override built-ins of the same name.
+
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::warn;
+
+use super::jsonrpc::JsonRpcError;
+use crate::state::AppState;
+
+const BUILTIN_PROMPTS: &[&str] = &[
+    include_str!("../../prompts/explain_file.json"),
+    include_str!("../../prompts/refactor_selection.json"),
+    include_str!("../../prompts/write_tests.json"),
+];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PromptArgument {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<PromptArgument>,
+    /// Not part of the `prompts/list` entry.
+    #[serde(skip_serializing)]
+    pub template: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetPromptParams {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: HashMap<String, String>,
+}
+
+pub async fn list_prompts(state: &Arc<AppState>) -> Vec<PromptTemplate> {
+    let mut prompts: Vec<PromptTemplate> = BUILTIN_PROMPTS.iter()
+        .map(|source| serde_json::from_str(source).expect("built-in prompt is valid"))
+        .collect();
+
+    let prompts_dir = state.config.read().await.mcp.prompts_dir.clone();
+    if let Some(dir) = prompts_dir {
+        for custom in load_dir(&dir).await {
+            prompts.retain(|prompt| prompt.name != custom.name);
+            prompts.push(custom);
+        }
+    }
+
+    prompts
+}
+
+pub async fn get_prompt(params: GetPromptParams, state: &Arc<AppState>) -> Result<Value, JsonRpcError> {
+    let prompt = list_prompts(state).await
+        .into_iter()
+        .find(|prompt| prompt.name == params.name)
+        .ok_or_else(|| JsonRpcError::invalid_params(format!("Unknown prompt: {}", params.name)))?;
+
+    let mut values = HashMap::new();
+    for argument in &prompt.arguments {
+        match params.arguments.get(&argument.name) {
+            Some(value) => {
+                values.insert(argument.name.clone(), value.clone());
+            }
+            None if argument.required => {
+                return Err(JsonRpcError::invalid_params(format!("Missing required argument: {}", argument.name)));
+            }
+            None => {
+                values.insert(argument.name.clone(), String::new());
+            }
+        }
+    }
+
+    if let Some(path) = params.arguments.get("path") {
+        fill_from_file(&mut values, path, &prompt.template, state).await?;
+    }
+
+    Ok(json!({
+        "description": prompt.description.unwrap_or(prompt.name),
+        "messages": [{
+            "role": "user",
+            "content": { "type": "text", "text": render(&prompt.template, &values) },
+        }],
+    }))
+}
+
+async fn fill_from_file(
+    values: &mut HashMap<String, String>,
+    path: &str,
+    template: &str,
+    state: &Arc<AppState>,
+) -> Result<(), JsonRpcError> {
+    let vfs = state.vfs.read().await;
+    let file = vfs.file_by_path(Path::new(path))?;
+
+    values.insert("language".to_string(), file.language.clone().unwrap_or_default());
+
+    if template.contains("{{selection}}") {
+        let selection = state.selection.read().await;
+        let text = selection.as_ref()
+            .filter(|selection| selection.file_id == file.id)
+            .map(|selection| selection.text(&file.content))
+            .transpose()?
+            .filter(|text| !text.is_empty())
+            .ok_or_else(|| JsonRpcError::invalid_params(format!("No editor selection in {}", path)))?;
+        values.insert("selection".to_string(), text);
+    }
+    values.insert("content".to_string(), file.content.to_string());
+
+    Ok(())
+}
+
+/// Expands `{{name}}` placeholders and `{{#name}}...{{/name}}` sections in a
+/// single pass, so substituted file content is never itself expanded.
+/// Unknown placeholders are left as written.
+fn render(template: &str, values: &HashMap<String, String>) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        text.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &after[..end];
+        rest = &after[end + 2..];
+
+        if let Some(name) = tag.strip_prefix('#') {
+            let close = format!("{{{{/{}}}}}", name);
+            let (body, remainder) = match rest.find(&close) {
+                Some(end) => (&rest[..end], &rest[end + close.len()..]),
+                None => (rest, ""),
+            };
+            if values.get(name).is_some_and(|value| !value.is_empty()) {
+                text.push_str(&render(body, values));
+            }
+            rest = remainder;
+        } else {
+            match values.get(tag) {
+                Some(value) => text.push_str(value),
+                None => text.push_str(&format!("{{{{{}}}}}", tag)),
+            }
+        }
+    }
+
+    text.push_str(rest);
+    text
+}
+
+async fn load_dir(dir: &Path) -> Vec<PromptTemplate> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        // A missing directory just means no custom prompts.
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => {
+            warn!("Failed to read prompts directory {}: {}", dir.display(), e);
+            return Vec::new();
+        }
+    };
+
+    let mut paths = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut prompts = Vec::new();
+    for path in paths {
+        let parsed = tokio::fs::read_to_string(&path).await
+            .map_err(|e| e.to_string())
+            .and_then(|source| serde_json::from_str::<PromptTemplate>(&source).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(prompt) => prompts.push(prompt),
+            Err(e) => warn!("Skipping prompt template {}: {}", path.display(), e),
+        }
+    }
+    prompts
+}
@@ -0,0 +1,146 @@
+//! VFS files as MCP resources. Each file is addressed as
+//! `abide://vfs/<path>`, and sessions subscribed to a URI receive
+//! `notifications/resources/updated` when the file is written or deleted.
+
+use dashmap::DashMap;
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::debug;
+
+use super::jsonrpc::{JsonRpcError, JsonRpcNotification, RESOURCE_NOT_FOUND};
+use crate::state::vfs::{ChangeListener, VirtualFile};
+use crate::state::{AppState, SessionState};
+
+pub const URI_PREFIX: &str = "abide://vfs/";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Resource {
+    pub uri: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub mime_type: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextResourceContents {
+    pub uri: String,
+    pub mime_type: String,
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResourceParams {
+    pub uri: String,
+}
+
+pub fn file_uri(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    format!("{}{}", URI_PREFIX, path.trim_start_matches('/'))
+}
+
+pub fn path_from_uri(uri: &str) -> Option<PathBuf> {
+    uri.strip_prefix(URI_PREFIX)
+        .map(|path| Path::new("/").join(path))
+}
+
+/// MIME hint for a file's detected language; unknown languages are plain text.
+pub fn mime_type(language: Option<&str>) -> &'static str {
+    match language {
+        Some("javascript") => "text/javascript",
+        Some("typescript") => "text/typescript",
+        Some("rust") => "text/x-rust",
+        Some("python") => "text/x-python",
+        Some("json") => "application/json",
+        Some("html") => "text/html",
+        Some("css") => "text/css",
+        Some("markdown") => "text/markdown",
+        _ => "text/plain",
+    }
+}
+
+fn resource(path: &Path, file: &VirtualFile) -> Resource {
+    Resource {
+        uri: file_uri(path),
+        name: file.name.clone(),
+        title: Some(path.to_string_lossy().to_string()),
+        mime_type: mime_type(file.language.as_deref()).to_string(),
+    }
+}
+
+pub async fn list_resources(state: &Arc<AppState>) -> Vec<Resource> {
+    let vfs = state.vfs.read().await;
+    vfs.files()
+        .into_iter()
+        .map(|(path, file)| resource(path, file))
+        .collect()
+}
+
+pub fn list_resource_templates() -> Value {
+    json!([{
+        "uriTemplate": format!("{}{{+path}}", URI_PREFIX),
+        "name": "vfs-file",
+        "title": "ABIDE workspace file",
+        "description": "A file in the ABIDE virtual file system, by absolute path",
+    }])
+}
+
+pub async fn read_resource(params: ResourceParams, state: &Arc<AppState>) -> Result<TextResourceContents, JsonRpcError> {
+    let vfs = state.vfs.read().await;
+    let file = path_from_uri(&params.uri)
+        .and_then(|path| vfs.file_by_path(&path).ok())
+        .ok_or_else(|| not_found(&params.uri))?;
+
+    Ok(TextResourceContents {
+        uri: params.uri,
+        mime_type: mime_type(file.language.as_deref()).to_string(),
+        text: file.content.to_string(),
+    })
+}
+
+pub async fn subscribe(params: ResourceParams, session_id: &str, state: &Arc<AppState>) -> Result<(), JsonRpcError> {
+    let exists = match path_from_uri(&params.uri) {
+        Some(path) => state.vfs.read().await.file_by_path(&path).is_ok(),
+        None => false,
+    };
+    if !exists {
+        return Err(not_found(&params.uri));
+    }
+
+    if let Some(mut session) = state.sessions.get_mut(session_id) {
+        session.resource_subscriptions.insert(params.uri);
+    }
+    Ok(())
+}
+
+pub fn unsubscribe(params: ResourceParams, session_id: &str, state: &Arc<AppState>) {
+    if let Some(mut session) = state.sessions.get_mut(session_id) {
+        session.resource_subscriptions.remove(&params.uri);
+    }
+}
+
+/// Builds the VFS listener that tells subscribed sessions about changes.
+pub fn change_listener(sessions: Arc<DashMap<String, SessionState>>) -> ChangeListener {
+    Arc::new(move |path| {
+        let uri = file_uri(path);
+        let notification = JsonRpcNotification::new(
+            "notifications/resources/updated",
+            Some(json!({ "uri": uri })),
+        );
+        let message = serde_json::to_string(&notification).unwrap();
+
+        for session in sessions.iter().filter(|session| session.resource_subscriptions.contains(&uri)) {
+            debug!("Resource {} updated, notifying session {}", uri, session.id);
+            // No attached stream means nobody is listening right now.
+            let _ = session.outbound.send(message.clone());
+        }
+    })
+}
+
+fn not_found(uri: &str) -> JsonRpcError {
+    JsonRpcError::new(RESOURCE_NOT_FOUND, "Resource not found").with_data(json!({ "uri": uri }))
+}
@@ -0,0 +1,68 @@
+//! MCP over stdio: one JSON-RPC message per line in each direction, as used
+//! by hosts that launch servers as subprocesses.
+
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+use crate::state::AppState;
+
+/// Serves a single MCP session until `reader` reaches EOF.
+pub async fn serve<R, W>(reader: R, writer: W, state: Arc<AppState>) -> std::io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let session_id = state.create_session();
+    info!("New MCP stdio session: {}", session_id);
+
+    let result = serve_session(reader, writer, &session_id, &state).await;
+
+    state.sessions.remove(&session_id);
+    info!("MCP stdio session terminated: {}", session_id);
+    result
+}
+
+async fn serve_session<R, W>(reader: R, mut writer: W, session_id: &str, state: &Arc<AppState>) -> std::io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = BufReader::new(reader).lines();
+    let Some(mut outbound) = state.subscribe_session(session_id) else {
+        return Ok(());
+    };
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line? else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                state.update_session_activity(session_id);
+                if let Some(response) = super::handle_message(&line, session_id, state).await {
+                    write_line(&mut writer, &response).await?;
+                }
+            }
+            // Server-initiated notifications, e.g. resource updates
+            message = outbound.recv() => match message {
+                Ok(message) => write_line(&mut writer, &message).await?,
+                Err(RecvError::Lagged(skipped)) => warn!("MCP stdio session dropped {} notifications", skipped),
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes one framed message. Serialized JSON never contains a raw newline,
+/// so no escaping is needed.
+pub async fn write_line<W: AsyncWrite + Unpin>(writer: &mut W, message: &str) -> std::io::Result<()> {
+    writer.write_all(message.as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    writer.flush().await
+}
@@ -0,0 +1,143 @@
+use std::sync::Arc;
+use schemars::{generate::SchemaSettings, JsonSchema};
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, Value};
+
+use super::jsonrpc::JsonRpcError;
+use super::operations::*;
+use crate::state::{AppState, Author};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolDefinition {
+    pub name: String,
+    pub description: String,
+    pub input_schema: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CallToolParams {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Option<Map<String, Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallToolResult {
+    pub content: Vec<ToolContent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
+    pub is_error: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ToolContent {
+    #[serde(rename = "text")]
+    Text { text: String },
+}
+
+/// A tool exposed over MCP and the `McpRequest` operation it dispatches to.
+/// The input schema is derived from the operation's parameter struct, so the
+/// advertised schema and the deserializer can't drift apart.
+struct Tool {
+    name: &'static str,
+    description: &'static str,
+    input_schema: fn() -> Value,
+    parse: fn(Value) -> serde_json::Result<McpRequest>,
+}
+
+macro_rules! tool {
+    ($name:literal, $variant:ident($params:ty), $description:literal) => {
+        Tool {
+            name: $name,
+            description: $description,
+            input_schema: input_schema::<$params>,
+            parse: |arguments| serde_json::from_value::<$params>(arguments).map(McpRequest::$variant),
+        }
+    };
+}
+
+const TOOLS: &[Tool] = &[
+    tool!("file_create", FileCreate(FileCreateParams), "Create a new file in the virtual file system"),
+    tool!("file_read", FileRead(FileReadParams), "Read a file from the virtual file system"),
+    tool!("file_update", FileUpdate(FileUpdateParams), "Replace the content of an existing file"),
+    tool!("file_delete", FileDelete(FileDeleteParams), "Delete a file from the virtual file system"),
+    tool!("file_edit", FileEdit(FileEditParams), "Apply non-overlapping line/column text edits to a file atomically"),
+    tool!("file_insert", FileInsert(FileInsertParams), "Insert text at an offset or line/column in a file"),
+    tool!("file_delete_range", FileDeleteRange(FileDeleteRangeParams), "Delete a range of text from a file and return it"),
+    tool!("file_replace_range", FileReplaceRange(FileReplaceRangeParams), "Replace a range of text in a file"),
+    tool!("file_patch", FilePatch(FilePatchParams), "Apply a unified diff across files, all or nothing, reporting rejected hunks"),
+    tool!("file_diff", FileDiff(FileDiffParams), "Diff two files as unified text and structured hunks"),
+    tool!("file_history", FileHistory(FileHistoryParams), "List a file's kept revisions with who made each change"),
+    tool!("file_revision", FileRevision(FileRevisionParams), "Read the content of one of a file's revisions"),
+    tool!("file_revert", FileRevert(FileRevertParams), "Restore a file to one of its revisions, as a new revision"),
+    tool!("file_undo", FileUndo(FileUndoParams), "Undo the last change to a file"),
+    tool!("file_redo", FileRedo(FileRedoParams), "Redo the last undone change to a file"),
+    tool!("file_rename", FileRename(FileRenameParams), "Rename a file or directory, keeping file ids"),
+    tool!("file_move", FileMove(FileMoveParams), "Move a file or directory to a new path, keeping file ids"),
+    tool!("file_copy", FileCopy(FileCopyParams), "Copy a file or directory subtree to a new path, with fresh ids"),
+    tool!("directory_create", DirectoryCreate(DirectoryCreateParams), "Create a new directory"),
+    tool!("directory_delete", DirectoryDelete(DirectoryDeleteParams), "Delete a directory, optionally with everything inside it"),
+    tool!("directory_list", DirectoryList(DirectoryListParams), "List the contents of a directory"),
+    tool!("batch", Batch(BatchParams), "Create, update, delete, mkdir and move in one all-or-nothing change, with per-operation results"),
+    tool!("snapshot_create", SnapshotCreate(SnapshotCreateParams), "Checkpoint every file and directory under a name"),
+    tool!("snapshot_list", SnapshotList(SnapshotListParams), "List the saved snapshots"),
+    tool!("snapshot_diff", SnapshotDiff(SnapshotDiffParams), "Diff a snapshot against the current files, as one patch"),
+    tool!("snapshot_restore", SnapshotRestore(SnapshotRestoreParams), "Roll every file and directory back to a snapshot"),
+    tool!("snapshot_delete", SnapshotDelete(SnapshotDeleteParams), "Delete a snapshot"),
+    tool!("animation_typing", AnimationType(AnimationTypeParams), "Start a typing animation into a file"),
+    tool!("animation_cursor", AnimationCursor(AnimationCursorParams), "Animate the editor cursor between two positions"),
+    tool!("settings_get", SettingsGet(SettingsGetParams), "Get the current ABIDE settings"),
+    tool!("settings_update", SettingsUpdate(SettingsUpdateParams), "Update ABIDE settings"),
+];
+
+/// JSON Schema (draft 2020-12) for a tool's arguments, with nested types
+/// inlined since MCP clients don't resolve `$ref`s.
+fn input_schema<T: JsonSchema>() -> Value {
+    let mut schema = SchemaSettings::draft2020_12()
+        .with(|settings| settings.inline_subschemas = true)
+        .into_generator()
+        .into_root_schema_for::<T>();
+    schema.remove("title");
+    schema.to_value()
+}
+
+pub fn list_tools() -> Vec<ToolDefinition> {
+    TOOLS.iter()
+        .map(|tool| ToolDefinition {
+            name: tool.name.to_string(),
+            description: tool.description.to_string(),
+            input_schema: (tool.input_schema)(),
+        })
+        .collect()
+}
+
+/// Runs a tool. Protocol errors (unknown tool, malformed arguments) are
+/// returned as `Err`; failures of the operation itself are reported in the
+/// result with `is_error` set so the model can see them.
+pub async fn call_tool(params: CallToolParams, session_id: &str, state: &Arc<AppState>) -> Result<CallToolResult, JsonRpcError> {
+    let tool = TOOLS.iter()
+        .find(|tool| tool.name == params.name)
+        .ok_or_else(|| JsonRpcError::invalid_params(format!("Unknown tool: {}", params.name)))?;
+
+    let arguments = Value::Object(params.arguments.unwrap_or_default());
+    let request = (tool.parse)(arguments)
+        .map_err(|e| JsonRpcError::invalid_params(format!("Invalid arguments for {}: {}", tool.name, e)))?;
+
+    Ok(match handle_mcp_request_as(request, &Author::agent(Some(session_id)), state).await {
+        McpResponse::Success { data } => CallToolResult {
+            content: vec![ToolContent::Text { text: data.to_string() }],
+            structured_content: data.is_object().then_some(data),
+            is_error: false,
+        },
+        McpResponse::Error { message, code, data } => CallToolResult {
+            structured_content: Some(serde_json::json!({
+                "error": { "code": code, "message": message, "data": data },
+            })),
+            content: vec![ToolContent::Text { text: message }],
+            is_error: true,
+        },
+    })
+}
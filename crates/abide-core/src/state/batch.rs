@@ -0,0 +1,105 @@
+//! Ordered VFS operations applied as one all-or-nothing change.
+
+use schemars::JsonSchema;
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+/// One step of a batch, e.g. `{"op": "create", "path": "/a.rs", "content": ""}`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum BatchOperation {
+    /// Create a file
+    Create {
+        /// Absolute path of the new file
+        path: String,
+        /// Initial file content
+        #[serde(default)]
+        content: String,
+        /// Create missing parent directories, like `mkdir -p`
+        #[serde(default)]
+        parents: bool,
+    },
+    /// Replace a file's content
+    Update {
+        /// File id, or the file's absolute path
+        #[serde(alias = "path")]
+        id: String,
+        /// New file content
+        content: String,
+        /// Fail unless the file is still at this version
+        #[serde(default)]
+        expected_version: Option<u64>,
+    },
+    /// Delete a file or directory
+    Delete {
+        /// File id, or the absolute path of a file or directory
+        #[serde(alias = "id")]
+        path: String,
+        /// Delete a directory with everything inside it
+        #[serde(default)]
+        recursive: bool,
+    },
+    /// Create a directory
+    Mkdir {
+        /// Absolute path of the new directory
+        path: String,
+        /// Create missing parent directories too
+        #[serde(default)]
+        parents: bool,
+    },
+    /// Move a file or directory, keeping file ids
+    Move {
+        /// Path of the file or directory to move
+        from: String,
+        /// Its new path
+        to: String,
+    },
+}
+
+/// The outcome of `VirtualFileSystem::apply_batch`, one result per
+/// operation in order.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BatchReport {
+    /// Whether the batch was kept; if any operation failed, none were
+    pub committed: bool,
+    pub results: Vec<OperationOutcome>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum OperationOutcome {
+    /// `id` is the file or directory created, updated or moved, and
+    /// `version` the file's version afterwards
+    Applied {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        version: Option<u64>,
+    },
+    /// Applied, then undone because a later operation failed
+    RolledBack,
+    /// Failed, so the whole batch was rolled back. `details` are the
+    /// error's own fields, e.g. the current version and content on a
+    /// version conflict.
+    Failed {
+        kind: String,
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        details: Option<Value>,
+    },
+    /// Not attempted after an earlier failure
+    Skipped,
+}
+
+impl BatchReport {
+    /// The failed operation and why.
+    pub fn summary(&self) -> String {
+        self.results.iter()
+            .enumerate()
+            .find_map(|(index, result)| match result {
+                OperationOutcome::Failed { message, .. } => Some(format!("operation {}: {}", index, message)),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+}
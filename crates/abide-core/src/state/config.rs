@@ -0,0 +1,198 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use std::path::PathBuf;
+
+use crate::error::{Error, Result};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AppConfig {
+    pub theme: Theme,
+    pub editor: EditorConfig,
+    pub animation: AnimationConfig,
+    pub api: ApiConfig,
+    #[serde(default)]
+    pub mcp: McpConfig,
+    #[serde(default)]
+    pub history: HistoryConfig,
+    #[serde(default)]
+    pub workspace: WorkspaceConfig,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Theme {
+    pub mode: String, // "light" or "dark"
+    pub primary_color: String,
+    pub secondary_color: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EditorConfig {
+    pub font_size: u32,
+    pub font_family: String,
+    pub tab_size: u32,
+    pub word_wrap: bool,
+    pub auto_save: bool,
+    pub auto_save_delay: u32, // milliseconds
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnimationConfig {
+    pub typing_speed: f32, // characters per second
+    pub typing_variation: f32, // randomness factor (0.0-1.0)
+    pub cursor_blink_rate: u32, // milliseconds
+    pub smooth_scrolling: bool,
+    pub enable_animations: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ApiConfig {
+    pub port: u16,
+    pub host: String,
+    pub max_connections: usize,
+    pub timeout: u32, // seconds
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct McpConfig {
+    /// Directory of custom prompt templates (`*.json`), loaded alongside
+    /// the built-in prompts
+    pub prompts_dir: Option<PathBuf>,
+}
+
+/// Limits on each file's revision history. The oldest revisions are
+/// dropped first; the current one is always kept.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    pub max_revisions: usize,
+    pub max_bytes: usize, // summed over a file's revisions
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WorkspaceConfig {
+    /// File the VFS is saved to and restored from; without one the VFS
+    /// only lives in memory
+    pub path: Option<PathBuf>,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            max_revisions: 100,
+            max_bytes: 8 * 1024 * 1024,
+        }
+    }
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            theme: Theme {
+                mode: "dark".to_string(),
+                primary_color: "#007ACC".to_string(),
+                secondary_color: "#1E1E1E".to_string(),
+            },
+            editor: EditorConfig {
+                font_size: 14,
+                font_family: "Cascadia Code, Consolas, monospace".to_string(),
+                tab_size: 4,
+                word_wrap: false,
+                auto_save: true,
+                auto_save_delay: 3000,
+            },
+            animation: AnimationConfig {
+                typing_speed: 80.0,
+                typing_variation: 0.2,
+                cursor_blink_rate: 530,
+                smooth_scrolling: true,
+                enable_animations: true,
+            },
+            api: ApiConfig {
+                port: 3030,
+                host: "127.0.0.1".to_string(),
+                max_connections: 100,
+                timeout: 30,
+            },
+            mcp: McpConfig::default(),
+            history: HistoryConfig::default(),
+            workspace: WorkspaceConfig::default(),
+        }
+    }
+}
+impl AppConfig {
+    /// Applies a partial settings object, e.g. `{"editor": {"tab_size": 2}}`,
+    /// to the user-facing sections: theme, editor, animation and history.
+    /// Keys it doesn't know, or whose values have the wrong type, are left
+    /// alone.
+    pub fn apply(&mut self, settings: &Value) -> Result<()> {
+        if !settings.is_object() {
+            return Err(Error::InvalidArgument("settings must be a JSON object".to_string()));
+        }
+        
+        // Update theme settings
+        if let Some(theme) = settings.get("theme") {
+            if let Some(mode) = theme.get("mode").and_then(|v| v.as_str()) {
+                self.theme.mode = mode.to_string();
+            }
+            if let Some(primary) = theme.get("primary_color").and_then(|v| v.as_str()) {
+                self.theme.primary_color = primary.to_string();
+            }
+            if let Some(secondary) = theme.get("secondary_color").and_then(|v| v.as_str()) {
+                self.theme.secondary_color = secondary.to_string();
+            }
+        }
+        
+        // Update editor settings
+        if let Some(editor) = settings.get("editor") {
+            if let Some(font_size) = editor.get("font_size").and_then(|v| v.as_u64()) {
+                self.editor.font_size = font_size as u32;
+            }
+            if let Some(font_family) = editor.get("font_family").and_then(|v| v.as_str()) {
+                self.editor.font_family = font_family.to_string();
+            }
+            if let Some(tab_size) = editor.get("tab_size").and_then(|v| v.as_u64()) {
+                self.editor.tab_size = tab_size as u32;
+            }
+            if let Some(word_wrap) = editor.get("word_wrap").and_then(|v| v.as_bool()) {
+                self.editor.word_wrap = word_wrap;
+            }
+            if let Some(auto_save) = editor.get("auto_save").and_then(|v| v.as_bool()) {
+                self.editor.auto_save = auto_save;
+            }
+            if let Some(auto_save_delay) = editor.get("auto_save_delay").and_then(|v| v.as_u64()) {
+                self.editor.auto_save_delay = auto_save_delay as u32;
+            }
+        }
+        
+        // Update animation settings
+        if let Some(animation) = settings.get("animation") {
+            if let Some(typing_speed) = animation.get("typing_speed").and_then(|v| v.as_f64()) {
+                self.animation.typing_speed = typing_speed as f32;
+            }
+            if let Some(typing_variation) = animation.get("typing_variation").and_then(|v| v.as_f64()) {
+                self.animation.typing_variation = typing_variation as f32;
+            }
+            if let Some(cursor_blink_rate) = animation.get("cursor_blink_rate").and_then(|v| v.as_u64()) {
+                self.animation.cursor_blink_rate = cursor_blink_rate as u32;
+            }
+            if let Some(smooth_scrolling) = animation.get("smooth_scrolling").and_then(|v| v.as_bool()) {
+                self.animation.smooth_scrolling = smooth_scrolling;
+            }
+            if let Some(enable_animations) = animation.get("enable_animations").and_then(|v| v.as_bool()) {
+                self.animation.enable_animations = enable_animations;
+            }
+        }
+        
+        // Update history limits
+        if let Some(history) = settings.get("history") {
+            if let Some(max_revisions) = history.get("max_revisions").and_then(|v| v.as_u64()) {
+                self.history.max_revisions = max_revisions as usize;
+            }
+            if let Some(max_bytes) = history.get("max_bytes").and_then(|v| v.as_u64()) {
+                self.history.max_bytes = max_bytes as usize;
+            }
+        }
+        
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+//! Line diffs between two texts, as unified diff text plus structured hunks.
+//! The unified text uses `a/` and `b/` paths, so it can be fed straight back
+//! into `file.patch`.
+
+use schemars::JsonSchema;
+use serde::{Serialize, Deserialize};
+use similar::{capture_diff_slices, group_diff_ops, Algorithm, DiffTag};
+
+/// Lines of unchanged context around each hunk by default, as in `diff -u`.
+pub const DEFAULT_CONTEXT: usize = 3;
+
+/// How whitespace differences count when matching lines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Whitespace {
+    /// Every whitespace change is a change
+    #[default]
+    Exact,
+    /// Ignore whitespace at the end of lines
+    IgnoreTrailing,
+    /// Ignore all whitespace, even where a line had none, like `diff -w`
+    IgnoreAll,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DiffOptions {
+    /// How whitespace differences count (default `exact`)
+    #[serde(default)]
+    pub whitespace: Whitespace,
+    /// Lines of unchanged context around each hunk (default 3)
+    pub context: Option<usize>,
+}
+
+/// One side of a diff: the current content of a file, one of its kept
+/// revisions, or its content in a snapshot.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DiffSide {
+    /// File id or absolute path
+    pub file: String,
+    /// Revision to use instead of the current content
+    #[serde(default)]
+    pub version: Option<u64>,
+    /// Snapshot to take the file from instead of the live tree
+    #[serde(default)]
+    pub snapshot: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FileDiff {
+    pub old_path: String,
+    pub new_path: String,
+    /// Unified diff text; empty when nothing changed
+    pub unified: String,
+    pub hunks: Vec<DiffHunk>,
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Every file that differs between two trees, by path. A file missing on
+/// one side is diffed against `/dev/null`, so `unified` applies as a patch.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WorkspaceDiff {
+    /// The per-file unified diffs, concatenated
+    pub unified: String,
+    pub files: Vec<FileDiff>,
+}
+
+/// A hunk as in a `@@ -old_start,old_lines +new_start,new_lines @@` header.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: LineKind,
+    pub text: String,
+    /// One-based line number in the old text, unless added
+    pub old_line: Option<usize>,
+    /// One-based line number in the new text, unless removed
+    pub new_line: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineKind {
+    Context,
+    Add,
+    Remove,
+}
+
+/// Diffs `old` against `new`, labelling them with their paths.
+pub fn diff_text(old: &str, new: &str, old_path: &str, new_path: &str, options: &DiffOptions) -> FileDiff {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let key = |line: &&str| match options.whitespace {
+        Whitespace::Exact => line.to_string(),
+        Whitespace::IgnoreTrailing => line.trim_end().to_string(),
+        Whitespace::IgnoreAll => line.chars().filter(|c| !c.is_whitespace()).collect(),
+    };
+    let mut old_keys: Vec<String> = old_lines.iter().map(key).collect();
+    let mut new_keys: Vec<String> = new_lines.iter().map(key).collect();
+    // A last line that only gained or lost its newline still changed
+    for (keys, text) in [(&mut old_keys, old), (&mut new_keys, new)] {
+        if let Some(last) = keys.last_mut().filter(|_| !text.ends_with('\n')) {
+            last.push('\0');
+        }
+    }
+
+    let ops = capture_diff_slices(Algorithm::Myers, &old_keys, &new_keys);
+    let context = options.context.unwrap_or(DEFAULT_CONTEXT);
+    let mut diff = FileDiff {
+        old_path: old_path.to_string(),
+        new_path: new_path.to_string(),
+        unified: String::new(),
+        hunks: Vec::new(),
+        added: 0,
+        removed: 0,
+    };
+
+    for group in group_diff_ops(ops, context) {
+        let mut lines = Vec::new();
+        for op in &group {
+            let (tag, old_range, new_range) = op.as_tag_tuple();
+            if tag == DiffTag::Equal {
+                for (old_index, new_index) in old_range.zip(new_range) {
+                    lines.push(DiffLine {
+                        kind: LineKind::Context,
+                        text: new_lines[new_index].to_string(),
+                        old_line: Some(old_index + 1),
+                        new_line: Some(new_index + 1),
+                    });
+                }
+                continue;
+            }
+            for old_index in old_range {
+                diff.removed += 1;
+                lines.push(DiffLine {
+                    kind: LineKind::Remove,
+                    text: old_lines[old_index].to_string(),
+                    old_line: Some(old_index + 1),
+                    new_line: None,
+                });
+            }
+            for new_index in new_range {
+                diff.added += 1;
+                lines.push(DiffLine {
+                    kind: LineKind::Add,
+                    text: new_lines[new_index].to_string(),
+                    old_line: None,
+                    new_line: Some(new_index + 1),
+                });
+            }
+        }
+
+        let (first, last) = (&group[0], &group[group.len() - 1]);
+        let (old_start, old_end) = (first.old_range().start, last.old_range().end);
+        let (new_start, new_end) = (first.new_range().start, last.new_range().end);
+        diff.hunks.push(DiffHunk {
+            // An empty side is numbered by the line before it, as `diff -u` does
+            old_start: if old_end > old_start { old_start + 1 } else { old_start },
+            old_lines: old_end - old_start,
+            new_start: if new_end > new_start { new_start + 1 } else { new_start },
+            new_lines: new_end - new_start,
+            lines,
+        });
+    }
+
+    if !diff.hunks.is_empty() {
+        diff.unified = unified(&diff, old, new);
+    }
+    diff
+}
+
+fn unified(diff: &FileDiff, old: &str, new: &str) -> String {
+    let label = |prefix: &str, path: &str| match path {
+        "/dev/null" => path.to_string(),
+        _ => format!("{}/{}", prefix, path.trim_start_matches('/')),
+    };
+    let mut text = format!("--- {}\n+++ {}\n", label("a", &diff.old_path), label("b", &diff.new_path));
+    let old_count = old.lines().count();
+    let new_count = new.lines().count();
+
+    for hunk in &diff.hunks {
+        text.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines
+        ));
+        for line in &hunk.lines {
+            let marker = match line.kind {
+                LineKind::Context => ' ',
+                LineKind::Add => '+',
+                LineKind::Remove => '-',
+            };
+            text.push(marker);
+            text.push_str(&line.text);
+            text.push('\n');
+
+            let old_last = line.old_line == Some(old_count) && line.kind != LineKind::Add && !old.ends_with('\n');
+            let new_last = line.new_line == Some(new_count) && line.kind != LineKind::Remove && !new.ends_with('\n');
+            if old_last || new_last {
+                text.push_str("\\ No newline at end of file\n");
+            }
+        }
+    }
+    text
+}
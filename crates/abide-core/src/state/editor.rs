@@ -0,0 +1,25 @@
+use ropey::Rope;
+use serde::{Serialize, Deserialize};
+
+use super::vfs::{char_index, TextPosition};
+use crate::error::Result;
+use crate::mcp::Position;
+
+/// The selection in the editor, as last reported by the frontend.
+/// Positions are zero-based with columns in UTF-16 code units, as in LSP.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EditorSelection {
+    pub file_id: String,
+    pub start: Position,
+    pub end: Position,
+}
+
+impl EditorSelection {
+    /// The selected text within `content`, read the same way as edits are
+    /// applied. Fails if either end is outside `content`.
+    pub fn text(&self, content: &Rope) -> Result<String> {
+        let start = char_index(content, &TextPosition::LineColumn(self.start.clone()))?;
+        let end = char_index(content, &TextPosition::LineColumn(self.end.clone()))?;
+        Ok(content.slice(start.min(end)..start.max(end)).to_string())
+    }
+}
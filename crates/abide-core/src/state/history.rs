@@ -0,0 +1,168 @@
+//! Bounded per-file revision history, so changes can be listed, undone,
+//! redone and reverted.
+
+use std::collections::VecDeque;
+use ropey::Rope;
+use serde::{Serialize, Deserialize};
+
+use super::config::HistoryConfig;
+
+/// Where a change came from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthorKind {
+    /// The desktop editor
+    #[default]
+    User,
+    /// An MCP client
+    Agent,
+    /// A REST client
+    Api,
+}
+
+/// Who made a change, recorded with each revision.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Author {
+    pub kind: AuthorKind,
+    /// MCP session that made the change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+}
+
+impl Author {
+    pub fn user() -> Self {
+        Self::default()
+    }
+    
+    pub fn agent(session_id: Option<&str>) -> Self {
+        Self {
+            kind: AuthorKind::Agent,
+            session_id: session_id.map(str::to_string),
+        }
+    }
+    
+    pub fn api() -> Self {
+        Self {
+            kind: AuthorKind::Api,
+            session_id: None,
+        }
+    }
+}
+
+/// A file's content as of one version. Ropes share unchanged chunks, so
+/// keeping many revisions of a large file is cheap.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Revision {
+    /// The file version this content was written as
+    pub version: u64,
+    /// Later versions at which undo or redo brought this content back
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub restored_as: Vec<u64>,
+    pub author: Author,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "super::vfs::rope_text")]
+    pub content: Rope,
+}
+
+/// A revision without its content, as listed to clients.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RevisionInfo {
+    pub version: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub restored_as: Vec<u64>,
+    pub author: Author,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub bytes: usize,
+    /// Whether the file's content is currently this revision's
+    pub current: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HistoryInfo {
+    /// Oldest first
+    pub revisions: Vec<RevisionInfo>,
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+/// Revisions of one file, oldest first. `current` is the revision the
+/// content matches; any after it are what redo steps through.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FileHistory {
+    revisions: VecDeque<Revision>,
+    current: usize,
+}
+
+impl FileHistory {
+    /// Records a new current revision, dropping anything that could have
+    /// been redone.
+    pub(crate) fn record(&mut self, revision: Revision, limits: &HistoryConfig) {
+        self.revisions.truncate(self.current + 1);
+        self.revisions.push_back(revision);
+        self.current = self.revisions.len() - 1;
+        self.trim(limits);
+    }
+    
+    /// Drops the oldest revisions, then redo steps, until within the limits.
+    pub(crate) fn trim(&mut self, limits: &HistoryConfig) {
+        while self.revisions.len() > 1
+            && (self.revisions.len() > limits.max_revisions || self.bytes() > limits.max_bytes)
+        {
+            if self.current > 0 {
+                self.revisions.pop_front();
+                self.current -= 1;
+            } else {
+                self.revisions.pop_back();
+            }
+        }
+    }
+    
+    pub(crate) fn undo(&mut self) -> Option<&Revision> {
+        self.current = self.current.checked_sub(1)?;
+        self.revisions.get(self.current)
+    }
+    
+    pub(crate) fn redo(&mut self) -> Option<&Revision> {
+        if self.current + 1 >= self.revisions.len() {
+            return None;
+        }
+        self.current += 1;
+        self.revisions.get(self.current)
+    }
+    
+    /// Notes that the current revision's content is now the file's at
+    /// `version`, after an undo or redo.
+    pub(crate) fn restored_as(&mut self, version: u64) {
+        if let Some(revision) = self.revisions.get_mut(self.current) {
+            revision.restored_as.push(version);
+        }
+    }
+    
+    /// The revision whose content the file had at `version`.
+    pub(crate) fn get(&self, version: u64) -> Option<&Revision> {
+        self.revisions.iter()
+            .find(|revision| revision.version == version || revision.restored_as.contains(&version))
+    }
+    
+    pub(crate) fn info(&self) -> HistoryInfo {
+        HistoryInfo {
+            revisions: self.revisions.iter()
+                .enumerate()
+                .map(|(index, revision)| RevisionInfo {
+                    version: revision.version,
+                    restored_as: revision.restored_as.clone(),
+                    author: revision.author.clone(),
+                    created_at: revision.created_at,
+                    bytes: revision.content.len_bytes(),
+                    current: index == self.current,
+                })
+                .collect(),
+            can_undo: self.current > 0,
+            can_redo: self.current + 1 < self.revisions.len(),
+        }
+    }
+    
+    fn bytes(&self) -> usize {
+        self.revisions.iter().map(|revision| revision.content.len_bytes()).sum()
+    }
+}
@@ -0,0 +1,168 @@
+use dashmap::DashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, watch, RwLock, RwLockWriteGuard};
+use tokio::time::Instant;
+use uuid::Uuid;
+
+pub mod vfs;
+pub mod batch;
+pub mod config;
+pub mod diff;
+pub mod editor;
+pub mod history;
+pub mod patch;
+pub mod workspace;
+
+pub use vfs::{VirtualFileSystem, VirtualFile, FileNode};
+pub use config::AppConfig;
+pub use editor::EditorSelection;
+pub use history::Author;
+
+/// Messages buffered per session before a slow stream starts missing them.
+const SESSION_OUTBOUND_CAPACITY: usize = 256;
+
+#[derive(Clone)]
+pub struct AppState {
+    pub vfs: Arc<RwLock<VirtualFileSystem>>,
+    pub config: Arc<RwLock<AppConfig>>,
+    pub sessions: Arc<DashMap<String, SessionState>>,
+    pub selection: Arc<RwLock<Option<EditorSelection>>>,
+    /// Set once the API server starts shutting down, so long-lived streams
+    /// end instead of holding the shutdown up
+    pub shutdown: Arc<watch::Sender<bool>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SessionState {
+    pub id: String,
+    pub connected_at: chrono::DateTime<chrono::Utc>,
+    /// When the client last sent anything, on tokio's clock
+    pub last_activity: Instant,
+    /// MCP protocol version agreed during `initialize`
+    pub protocol_version: Option<String>,
+    pub client_info: Option<serde_json::Value>,
+    pub client_capabilities: serde_json::Value,
+    /// Set once the client sends `notifications/initialized`
+    pub initialized: bool,
+    /// Server-initiated messages for whichever streams the transport has
+    /// attached to this session
+    pub outbound: broadcast::Sender<String>,
+    /// Resource URIs the client asked to hear about via `resources/subscribe`
+    pub resource_subscriptions: HashSet<String>,
+}
+
+#[allow(dead_code)]
+impl SessionState {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+    
+    pub fn connected_at(&self) -> &chrono::DateTime<chrono::Utc> {
+        &self.connected_at
+    }
+}
+
+impl AppState {
+    pub fn new(vfs: VirtualFileSystem) -> Self {
+        Self::with_config(vfs, AppConfig::default())
+    }
+    
+    pub fn with_config(mut vfs: VirtualFileSystem, config: AppConfig) -> Self {
+        let sessions = Arc::new(DashMap::new());
+        vfs.set_change_listener(crate::mcp::resources::change_listener(sessions.clone()));
+        vfs.set_history_limits(config.history.clone());
+        
+        Self {
+            vfs: Arc::new(RwLock::new(vfs)),
+            config: Arc::new(RwLock::new(config)),
+            sessions,
+            selection: Arc::new(RwLock::new(None)),
+            shutdown: Arc::new(watch::channel(false).0),
+        }
+    }
+    
+    /// Write access to the VFS, with the changes made through it recorded
+    /// as `author`'s.
+    pub async fn vfs_as(&self, author: &Author) -> RwLockWriteGuard<'_, VirtualFileSystem> {
+        let mut vfs = self.vfs.write().await;
+        vfs.set_author(author.clone());
+        vfs
+    }
+    
+    /// Applies a partial settings object (see `AppConfig::apply`) and
+    /// passes new history limits on to the VFS. Returns the new settings.
+    pub async fn update_settings(&self, settings: &serde_json::Value) -> crate::error::Result<AppConfig> {
+        let mut config = self.config.write().await;
+        config.apply(settings)?;
+        if settings.get("history").is_some() {
+            self.vfs.write().await.set_history_limits(config.history.clone());
+        }
+        Ok(config.clone())
+    }
+    
+    /// Tells open streams the server is shutting down.
+    pub fn begin_shutdown(&self) {
+        self.shutdown.send_replace(true);
+    }
+    
+    /// Resolves once `begin_shutdown` has been called.
+    pub fn shutting_down(&self) -> impl std::future::Future<Output = ()> + Send + 'static {
+        let mut shutdown = self.shutdown.subscribe();
+        async move {
+            let _ = shutdown.wait_for(|stopping| *stopping).await;
+        }
+    }
+    
+    pub fn create_session(&self) -> String {
+        let session_id = Uuid::new_v4().to_string();
+        let session = SessionState {
+            id: session_id.clone(),
+            connected_at: chrono::Utc::now(),
+            last_activity: Instant::now(),
+            protocol_version: None,
+            client_info: None,
+            client_capabilities: serde_json::Value::Null,
+            initialized: false,
+            outbound: broadcast::channel(SESSION_OUTBOUND_CAPACITY).0,
+            resource_subscriptions: HashSet::new(),
+        };
+        
+        self.sessions.insert(session_id.clone(), session);
+        session_id
+    }
+    
+    pub fn update_session_activity(&self, session_id: &str) {
+        if let Some(mut session) = self.sessions.get_mut(session_id) {
+            session.last_activity = Instant::now();
+        }
+    }
+    
+    /// Removes sessions that have been idle for `idle` and have no stream
+    /// attached, returning their ids. Connected WebSocket and stdio sessions
+    /// always have one, so only abandoned HTTP sessions go.
+    pub fn evict_idle_sessions(&self, idle: Duration) -> Vec<String> {
+        let mut evicted = Vec::new();
+        self.sessions.retain(|session_id, session| {
+            let keep = session.outbound.receiver_count() > 0 || session.last_activity.elapsed() < idle;
+            if !keep {
+                evicted.push(session_id.clone());
+            }
+            keep
+        });
+        evicted
+    }
+    
+    /// Attaches a stream to the session's server-initiated messages.
+    pub fn subscribe_session(&self, session_id: &str) -> Option<broadcast::Receiver<String>> {
+        self.sessions.get(session_id).map(|session| session.outbound.subscribe())
+    }
+    
+    /// Sends a serialized message to the session. Returns false when the
+    /// session is gone or has no stream attached to receive it.
+    pub fn send_to_session(&self, session_id: &str, message: String) -> bool {
+        self.sessions.get(session_id)
+            .is_some_and(|session| session.outbound.send(message).is_ok())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,399 @@
+//! Unified diff parsing and hunk matching for `file.patch`.
+//!
+//! Parsing is lenient in the ways model-written diffs tend to go wrong:
+//! hunk line counts are not trusted, `@@ @@` headers without line numbers
+//! are accepted, and blank context lines may have lost their leading space.
+//! Hunks may apply at an offset from their stated line, ignoring trailing
+//! whitespace, and with up to `fuzz` context lines dropped at either end.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::vfs::normalize_path;
+use crate::error::{Error, Result};
+
+/// Context lines a hunk may lose at each end and still apply.
+pub const DEFAULT_FUZZ: usize = 2;
+
+/// The changes a diff makes to one file. A missing path is `/dev/null`:
+/// no `old_path` creates the file, no `new_path` deletes it.
+#[derive(Clone, Debug)]
+pub struct FilePatch {
+    pub old_path: Option<PathBuf>,
+    pub new_path: Option<PathBuf>,
+    pub hunks: Vec<Hunk>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Hunk {
+    /// One-based start line in the old file, if the header gave one
+    pub old_start: Option<usize>,
+    pub header: String,
+    pub lines: Vec<HunkLine>,
+    /// `\ No newline at end of file` followed the old side's last line
+    pub old_missing_newline: bool,
+    /// `\ No newline at end of file` followed the new side's last line
+    pub new_missing_newline: bool,
+}
+
+#[derive(Clone, Debug)]
+pub enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+impl Hunk {
+    fn old_lines(&self) -> Vec<&str> {
+        self.lines.iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Remove(text) => Some(text.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect()
+    }
+
+    fn new_lines(&self) -> Vec<&str> {
+        self.lines.iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Add(text) => Some(text.as_str()),
+                HunkLine::Remove(_) => None,
+            })
+            .collect()
+    }
+
+    /// Context lines before the first change and after the last one.
+    fn context_margins(&self) -> (usize, usize) {
+        let is_context = |line: &&HunkLine| matches!(line, HunkLine::Context(_));
+        let leading = self.lines.iter().take_while(is_context).count();
+        let trailing = self.lines.iter().rev().take_while(is_context).count();
+        if leading == self.lines.len() {
+            (leading, 0)
+        } else {
+            (leading, trailing)
+        }
+    }
+}
+
+/// The outcome of `VirtualFileSystem::apply_patch`, per file and hunk.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PatchReport {
+    pub files: Vec<FilePatchReport>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FilePatchReport {
+    pub path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub renamed_from: Option<PathBuf>,
+    pub action: PatchAction,
+    /// Why the file as a whole could not be patched
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub hunks: Vec<HunkReport>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatchAction {
+    Create,
+    Modify,
+    Rename,
+    Delete,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HunkReport {
+    pub header: String,
+    #[serde(flatten)]
+    pub outcome: HunkOutcome,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum HunkOutcome {
+    /// `line` is one-based in the patched file; `offset` is how far that is
+    /// from where the header said, after earlier hunks
+    Applied { line: usize, offset: isize, fuzz: usize },
+    /// `expected` is the old text the hunk was looking for
+    Rejected { reason: String, expected: String },
+}
+
+impl FilePatchReport {
+    pub fn is_rejected(&self) -> bool {
+        self.error.is_some()
+            || self.hunks.iter().any(|hunk| matches!(hunk.outcome, HunkOutcome::Rejected { .. }))
+    }
+}
+
+impl PatchReport {
+    pub fn is_rejected(&self) -> bool {
+        self.files.iter().any(FilePatchReport::is_rejected)
+    }
+
+    /// One line per failed file or hunk.
+    pub fn summary(&self) -> String {
+        let mut lines = Vec::new();
+        for file in self.files.iter().filter(|file| file.is_rejected()) {
+            if let Some(error) = &file.error {
+                lines.push(format!("{}: {}", file.path.display(), error));
+            }
+            for hunk in &file.hunks {
+                if let HunkOutcome::Rejected { reason, .. } = &hunk.outcome {
+                    lines.push(format!("{}: hunk {} rejected: {}", file.path.display(), hunk.header, reason));
+                }
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+/// Splits a unified diff into per-file patches. Text outside file sections
+/// (`diff --git`, `index` lines, commentary) is ignored.
+pub fn parse(diff: &str) -> Result<Vec<FilePatch>> {
+    let lines: Vec<&str> = diff.lines().collect();
+    let mut patches = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        if !is_file_header(&lines, i) {
+            i += 1;
+            continue;
+        }
+        let old_path = parse_path(&lines[i][4..])?;
+        let new_path = parse_path(&lines[i + 1][4..])?;
+        if old_path.is_none() && new_path.is_none() {
+            return Err(Error::InvalidArgument("patch has /dev/null on both sides".to_string()));
+        }
+        i += 2;
+
+        let mut hunks = Vec::new();
+        while i < lines.len() && lines[i].starts_with("@@") {
+            let (hunk, next) = parse_hunk(&lines, i)?;
+            hunks.push(hunk);
+            i = next;
+        }
+        patches.push(FilePatch { old_path, new_path, hunks });
+    }
+
+    if patches.is_empty() {
+        return Err(Error::InvalidArgument("no `---`/`+++` file headers found in patch".to_string()));
+    }
+    Ok(patches)
+}
+
+fn is_file_header(lines: &[&str], i: usize) -> bool {
+    lines[i].starts_with("--- ") && lines.get(i + 1).is_some_and(|next| next.starts_with("+++ "))
+}
+
+/// `a/src/main.js`, `b/src/main.js`, `/src/main.js` and `src/main.js` all
+/// name `/src/main.js`; a tab-separated timestamp is dropped.
+fn parse_path(header: &str) -> Result<Option<PathBuf>> {
+    let path = header.split('\t').next().unwrap_or("").trim();
+    if path == "/dev/null" {
+        return Ok(None);
+    }
+    let path = path.strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    if path.is_empty() {
+        return Err(Error::InvalidArgument("patch file header has no path".to_string()));
+    }
+    normalize_path(Path::new(path)).map(Some)
+}
+
+fn parse_hunk(lines: &[&str], start: usize) -> Result<(Hunk, usize)> {
+    let header = lines[start];
+    let ranges = header.trim_start_matches('@')
+        .split("@@")
+        .next()
+        .unwrap_or("");
+    let mut old_start = None;
+    let mut old_count = None;
+    for token in ranges.split_whitespace() {
+        if let Some(range) = token.strip_prefix('-') {
+            let mut parts = range.splitn(2, ',');
+            old_start = Some(parse_number(parts.next(), header)?);
+            old_count = Some(parts.next().map_or(Ok(1), |count| parse_number(Some(count), header))?);
+        }
+    }
+
+    let mut hunk = Hunk {
+        old_start,
+        header: header.trim().to_string(),
+        lines: Vec::new(),
+        old_missing_newline: false,
+        new_missing_newline: false,
+    };
+    let mut old_left = old_count.unwrap_or(0);
+    let mut i = start + 1;
+
+    while i < lines.len() {
+        let line = lines[i];
+        if line.starts_with("@@") || line.starts_with("diff ") || (is_file_header(lines, i) && old_left == 0) {
+            break;
+        }
+        match line.chars().next() {
+            Some(' ') => {
+                hunk.lines.push(HunkLine::Context(line[1..].to_string()));
+                old_left = old_left.saturating_sub(1);
+            }
+            Some('-') => {
+                hunk.lines.push(HunkLine::Remove(line[1..].to_string()));
+                old_left = old_left.saturating_sub(1);
+            }
+            Some('+') => hunk.lines.push(HunkLine::Add(line[1..].to_string())),
+            Some('\\') => match hunk.lines.last() {
+                Some(HunkLine::Remove(_)) => hunk.old_missing_newline = true,
+                Some(HunkLine::Add(_)) => hunk.new_missing_newline = true,
+                _ => {
+                    hunk.old_missing_newline = true;
+                    hunk.new_missing_newline = true;
+                }
+            },
+            // A blank context line that lost its leading space
+            None if old_left > 0 || continues_hunk(lines, i + 1) => {
+                hunk.lines.push(HunkLine::Context(String::new()));
+                old_left = old_left.saturating_sub(1);
+            }
+            _ => break,
+        }
+        i += 1;
+    }
+
+    Ok((hunk, i))
+}
+
+fn continues_hunk(lines: &[&str], i: usize) -> bool {
+    lines.get(i).is_some_and(|line| {
+        (line.starts_with(' ') || line.starts_with('+') || line.starts_with('-')) && !is_file_header(lines, i)
+    })
+}
+
+fn parse_number(text: Option<&str>, header: &str) -> Result<usize> {
+    text.and_then(|text| text.parse().ok())
+        .ok_or_else(|| Error::InvalidArgument(format!("malformed hunk header: {}", header)))
+}
+
+/// Applies hunks to `content` in order. Returns the new content when every
+/// hunk applied, and a report for each hunk either way.
+pub fn apply_hunks(content: &str, hunks: &[Hunk], fuzz: usize) -> (Option<String>, Vec<HunkReport>) {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut trailing_newline = content.ends_with('\n');
+    let line_ending = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut reports = Vec::with_capacity(hunks.len());
+    let mut rejected = false;
+    // Lines added minus lines removed so far, to map header line numbers
+    let mut shift: isize = 0;
+    // Hunks apply in order and may not overlap
+    let mut floor = 0;
+
+    for hunk in hunks {
+        let old = hunk.old_lines();
+        let new = hunk.new_lines();
+        // `-5,0` inserts after line 5; otherwise the hunk starts at line 5
+        let expected = hunk.old_start.map(|start| {
+            let index = if old.is_empty() { start } else { start.saturating_sub(1) };
+            (index as isize + shift).max(0) as usize
+        });
+
+        let Some(found) = find_hunk(&lines, hunk, &old, expected, floor, fuzz) else {
+            rejected = true;
+            reports.push(HunkReport {
+                header: hunk.header.clone(),
+                outcome: HunkOutcome::Rejected {
+                    reason: if old.is_empty() {
+                        "insertion point is past the end of the file".to_string()
+                    } else {
+                        format!("no match for its {} old line(s), even with fuzz {}", old.len(), fuzz)
+                    },
+                    expected: old.join("\n"),
+                },
+            });
+            continue;
+        };
+
+        let old_part = &old[found.leading..old.len() - found.trailing];
+        let new_part = &new[found.leading..new.len() - found.trailing];
+        let at_end = found.start + old_part.len() == lines.len() && found.trailing == 0;
+        lines.splice(found.start..found.start + old_part.len(), new_part.iter().map(|line| line.to_string()));
+
+        if at_end {
+            if hunk.new_missing_newline {
+                trailing_newline = false;
+            } else if hunk.old_missing_newline || (content.is_empty() && !new_part.is_empty()) {
+                trailing_newline = true;
+            }
+        }
+
+        let start = found.start.saturating_sub(found.leading);
+        reports.push(HunkReport {
+            header: hunk.header.clone(),
+            outcome: HunkOutcome::Applied {
+                line: start + 1,
+                offset: expected.map_or(0, |expected| start as isize - expected as isize),
+                fuzz: found.fuzz,
+            },
+        });
+        floor = found.start + new_part.len();
+        shift += new.len() as isize - old.len() as isize;
+    }
+
+    if rejected {
+        return (None, reports);
+    }
+    let mut text = lines.join(line_ending);
+    if trailing_newline && !lines.is_empty() {
+        text.push_str(line_ending);
+    }
+    (Some(text), reports)
+}
+
+struct Match {
+    /// Index in the file of the first line that was matched
+    start: usize,
+    /// Context lines ignored at the start and end of the hunk
+    leading: usize,
+    trailing: usize,
+    fuzz: usize,
+}
+
+/// Finds where a hunk's old lines occur, nearest to `expected` first,
+/// dropping more context lines at each fuzz level.
+fn find_hunk(lines: &[String], hunk: &Hunk, old: &[&str], expected: Option<usize>, floor: usize, max_fuzz: usize) -> Option<Match> {
+    if old.is_empty() {
+        let start = expected.unwrap_or(lines.len()).max(floor);
+        return (start <= lines.len()).then_some(Match { start, leading: 0, trailing: 0, fuzz: 0 });
+    }
+
+    let (context_before, context_after) = hunk.context_margins();
+    let mut tried = Vec::new();
+    for fuzz in 0..=max_fuzz {
+        let leading = fuzz.min(context_before);
+        let trailing = fuzz.min(context_after);
+        if tried.contains(&(leading, trailing)) || leading + trailing >= old.len() {
+            continue;
+        }
+        tried.push((leading, trailing));
+
+        let pattern = &old[leading..old.len() - trailing];
+        if lines.len() < floor + pattern.len() {
+            continue;
+        }
+        let last = lines.len() - pattern.len();
+        let matches_at = |start: usize| {
+            pattern.iter().zip(&lines[start..]).all(|(want, have)| want.trim_end() == have.trim_end())
+        };
+
+        let origin = expected.map_or(floor, |expected| (expected + leading).clamp(floor, last));
+        for distance in 0..=(last - floor) {
+            let candidates = [origin.checked_add(distance), origin.checked_sub(distance)];
+            for start in candidates.into_iter().flatten() {
+                if (floor..=last).contains(&start) && matches_at(start) {
+                    return Some(Match { start, leading, trailing, fuzz });
+                }
+            }
+        }
+    }
+    None
+}
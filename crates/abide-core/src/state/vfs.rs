@@ -0,0 +1,1437 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use im::HashMap;
+use ropey::Rope;
+use schemars::JsonSchema;
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::batch::{BatchOperation, BatchReport, OperationOutcome};
+use super::config::HistoryConfig;
+use super::diff::{self, DiffOptions, DiffSide, FileDiff, WorkspaceDiff};
+use super::history::{Author, FileHistory, HistoryInfo, Revision};
+use super::patch::{self, FilePatchReport, PatchAction, PatchReport};
+use super::workspace::{SavedNode, Workspace, WORKSPACE_FORMAT};
+use crate::error::{Error, Result};
+use crate::mcp::Position;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VirtualFile {
+    pub id: String,
+    pub name: String,
+    /// Serialized as a plain string
+    #[serde(with = "rope_text")]
+    pub content: Rope,
+    pub language: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub modified_at: chrono::DateTime<chrono::Utc>,
+    /// Starts at 1 and goes up by one with every content change
+    pub version: u64,
+}
+
+/// A place in a file's text: an offset from the start, or a zero-based line
+/// and column. Offsets and columns count UTF-16 code units, as in LSP and
+/// Monaco's `getOffsetAt`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum TextPosition {
+    Offset {
+        /// Zero-based offset from the start of the file in UTF-16 code units
+        offset: usize,
+    },
+    LineColumn(Position),
+}
+
+/// A range between two zero-based line/column positions, end exclusive.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TextRange {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// Replaces `range` with `new_text`, as in LSP's `TextEdit`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TextEdit {
+    pub range: TextRange,
+    /// Replacement text; empty to delete the range
+    #[serde(alias = "newText")]
+    pub new_text: String,
+}
+
+/// A file or directory with its whole subtree, as sent to clients. This is
+/// a view built on demand; the VFS itself stores a flat node table.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FileNode {
+    pub id: String,
+    pub name: String,
+    pub path: PathBuf,
+    pub is_directory: bool,
+    pub children: Vec<FileNode>,
+    pub file_id: Option<String>, // Reference to VirtualFile if not a directory
+}
+
+/// What `copy_node` does when a destination file already exists.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictPolicy {
+    /// Refuse the copy if the destination exists
+    #[default]
+    Fail,
+    /// Keep existing files and copy the rest
+    Skip,
+    /// Replace the content of existing files
+    Overwrite,
+}
+
+/// Paths touched by a `copy_node`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CopyReport {
+    pub created: Vec<PathBuf>,
+    pub overwritten: Vec<PathBuf>,
+    pub skipped: Vec<PathBuf>,
+}
+
+/// Paths removed by a `delete_directory`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DeleteReport {
+    pub removed_files: Vec<PathBuf>,
+    pub removed_directories: Vec<PathBuf>,
+}
+
+/// A named snapshot, as listed to clients.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    pub name: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub files: usize,
+}
+
+/// Files changed by a `restore_snapshot`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RestoreReport {
+    pub modified: Vec<PathBuf>,
+    pub recreated: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+}
+
+/// Called with the path of a file after its content changes or it is deleted.
+pub type ChangeListener = Arc<dyn Fn(&Path) + Send + Sync>;
+
+/// An entry in the node table. Nodes link to each other by id; `path` is
+/// cached so lookups and `path_of` don't have to walk parents.
+#[derive(Clone, Debug)]
+struct Node {
+    id: String,
+    name: String,
+    path: PathBuf,
+    parent: Option<String>,
+    /// Child node ids, in creation order
+    children: Vec<String>,
+    file_id: Option<String>,
+}
+
+/// The tree and files as they were when a snapshot was taken. The tables
+/// are persistent maps that share their structure with the live ones, so
+/// taking a snapshot copies nothing; later changes on either side copy only
+/// the entries they touch, and file contents are ropes sharing their chunks.
+#[derive(Clone, Debug)]
+struct Snapshot {
+    info: SnapshotInfo,
+    files: HashMap<String, VirtualFile>,
+    nodes: HashMap<String, Node>,
+    paths: HashMap<PathBuf, String>,
+    file_nodes: HashMap<String, String>,
+}
+
+impl Snapshot {
+    /// Content by path, for diffing against another tree.
+    fn contents(&self) -> BTreeMap<&Path, &Rope> {
+        self.file_nodes.iter()
+            .map(|(file_id, node_id)| (self.nodes[node_id].path.as_path(), &self.files[file_id].content))
+            .collect()
+    }
+}
+
+impl Node {
+    fn is_directory(&self) -> bool {
+        self.file_id.is_none()
+    }
+}
+
+/// The tables are persistent `im` maps: a clone shares them until either
+/// copy changes, which is what keeps snapshots and batch staging cheap.
+#[derive(Clone)]
+pub struct VirtualFileSystem {
+    files: HashMap<String, VirtualFile>,
+    /// Every file and directory node, keyed by node id
+    nodes: HashMap<String, Node>,
+    /// Normalized absolute path to node id
+    paths: HashMap<PathBuf, String>,
+    /// File id to the id of the node holding it
+    file_nodes: HashMap<String, String>,
+    root_id: String,
+    /// Revision history per file id
+    histories: HashMap<String, FileHistory>,
+    history_limits: HistoryConfig,
+    /// Credited with changes until another author is set
+    author: Author,
+    /// Named snapshots, oldest first
+    snapshots: Vec<Snapshot>,
+    /// Goes up with every change to the tree or to a file's content
+    changes: u64,
+    on_change: Option<ChangeListener>,
+}
+
+impl fmt::Debug for VirtualFileSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VirtualFileSystem")
+            .field("files", &self.files)
+            .field("nodes", &self.nodes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for VirtualFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VirtualFileSystem {
+    pub fn new() -> Self {
+        let root = Node {
+            id: Uuid::new_v4().to_string(),
+            name: "/".to_string(),
+            path: PathBuf::from("/"),
+            parent: None,
+            children: vec![],
+            file_id: None,
+        };
+    
+        Self {
+            files: HashMap::new(),
+            paths: HashMap::unit(root.path.clone(), root.id.clone()),
+            root_id: root.id.clone(),
+            nodes: HashMap::unit(root.id.clone(), root),
+            file_nodes: HashMap::new(),
+            histories: HashMap::new(),
+            history_limits: HistoryConfig::default(),
+            author: Author::default(),
+            snapshots: Vec::new(),
+            changes: 0,
+            on_change: None,
+        }
+    }
+    
+    pub fn set_change_listener(&mut self, listener: ChangeListener) {
+        self.on_change = Some(listener);
+    }
+    
+    /// Sets who the following changes are recorded as made by.
+    pub fn set_author(&mut self, author: Author) {
+        self.author = author;
+    }
+    
+    /// Applies new history limits, trimming existing histories to fit.
+    pub fn set_history_limits(&mut self, limits: HistoryConfig) {
+        for (_, history) in self.histories.iter_mut() {
+            history.trim(&limits);
+        }
+        self.history_limits = limits;
+    }
+    
+    /// A counter that goes up whenever the tree or a file's content changes,
+    /// so savers can tell whether there is anything new.
+    pub fn changes(&self) -> u64 {
+        self.changes
+    }
+    
+    /// The tree, contents and metadata as a workspace file.
+    pub fn to_workspace(&self) -> Workspace {
+        let nodes = self.subtree(&self.root_id)
+            .into_iter()
+            .skip(1)
+            .map(|id| {
+                let node = &self.nodes[&id];
+                SavedNode {
+                    id: node.id.clone(),
+                    path: node.path.clone(),
+                    file: node.file_id.as_ref().map(|file_id| self.files[file_id].clone()),
+                }
+            })
+            .collect();
+    
+        Workspace {
+            format: WORKSPACE_FORMAT,
+            saved_at: chrono::Utc::now(),
+            root_id: self.root_id.clone(),
+            nodes,
+        }
+    }
+    
+    /// Rebuilds a VFS from a workspace file, keeping node and file ids and
+    /// versions. Each file's history starts over at its saved content.
+    pub fn from_workspace(workspace: Workspace) -> Result<Self> {
+        if workspace.format != WORKSPACE_FORMAT {
+            return Err(Error::InvalidArgument(format!(
+                "workspace format {} is not supported, expected {}",
+                workspace.format, WORKSPACE_FORMAT
+            )));
+        }
+    
+        let mut vfs = Self::new();
+        let mut root = vfs.nodes.remove(&vfs.root_id).unwrap();
+        root.id = workspace.root_id;
+        vfs.root_id = root.id.clone();
+        vfs.paths.insert(root.path.clone(), root.id.clone());
+        vfs.nodes.insert(root.id.clone(), root);
+    
+        for saved in workspace.nodes {
+            let path = normalize_path(&saved.path)?;
+            if vfs.paths.contains_key(&path) {
+                return Err(Error::AlreadyExists(path));
+            }
+            if vfs.nodes.contains_key(&saved.id) {
+                return Err(Error::Conflict(format!("duplicate node id {}", saved.id)));
+            }
+    
+            let Some(mut file) = saved.file else {
+                vfs.insert_node(&path, saved.id, None)?;
+                continue;
+            };
+            if vfs.files.contains_key(&file.id) {
+                return Err(Error::Conflict(format!("duplicate file id {}", file.id)));
+            }
+            vfs.insert_node(&path, saved.id, Some(file.id.clone()))?;
+            file.name = vfs.nodes[&vfs.paths[&path]].name.clone();
+            let file_id = file.id.clone();
+            vfs.files.insert(file_id.clone(), file);
+            vfs.record_revision(&file_id);
+        }
+    
+        Ok(vfs)
+    }
+    
+    pub fn create_file(&mut self, path: &Path, content: String) -> Result<String> {
+        let path = normalize_path(path)?;
+        let file_name = path.file_name()
+            .ok_or_else(|| Error::invalid_path(&path, "missing file name"))?
+            .to_string_lossy()
+            .to_string();
+        if self.paths.contains_key(&path) {
+            return Err(Error::AlreadyExists(path));
+        }
+    
+        let file_id = Uuid::new_v4().to_string();
+        let now = chrono::Utc::now();
+    
+        let file = VirtualFile {
+            id: file_id.clone(),
+            name: file_name.clone(),
+            content: Rope::from(content),
+            language: detect_language(&file_name),
+            created_at: now,
+            modified_at: now,
+            version: 1,
+        };
+    
+        self.insert_node(&path, Uuid::new_v4().to_string(), Some(file_id.clone()))?;
+        self.files.insert(file_id.clone(), file);
+        self.record_revision(&file_id);
+    
+        Ok(file_id)
+    }
+    
+    /// Like `create_file`, but first creates any missing parent directories.
+    pub fn create_file_all(&mut self, path: &Path, content: String) -> Result<String> {
+        let path = normalize_path(path)?;
+        if let Some(parent) = path.parent() {
+            self.create_directory_all(parent)?;
+        }
+        self.create_file(&path, content)
+    }
+    
+    pub fn read_file(&self, file_id: &str) -> Result<&VirtualFile> {
+        self.files.get(file_id)
+            .ok_or_else(|| Error::not_found(file_id))
+    }
+    
+    /// Fails with `VersionConflict`, carrying the current content, if a
+    /// version is expected and the file has moved on from it.
+    pub fn check_version(&self, file_id: &str, expected: Option<u64>) -> Result<()> {
+        let file = self.read_file(file_id)?;
+        match expected {
+            Some(expected) if expected != file.version => Err(Error::VersionConflict {
+                file: self.path_of(file_id).map_or_else(|| file_id.to_string(), |path| path.display().to_string()),
+                expected,
+                current: file.version,
+                content: file.content.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+    
+    pub fn write_file(&mut self, file_id: &str, content: String) -> Result<()> {
+        let file = self.files.get_mut(file_id)
+            .ok_or_else(|| Error::not_found(file_id))?;
+    
+        file.content = Rope::from(content);
+        self.content_changed(file_id);
+        Ok(())
+    }
+    
+    /// Inserts `text` at a position in the file. Returns the new version.
+    pub fn insert_text(&mut self, file_id: &str, at: &TextPosition, text: &str) -> Result<u64> {
+        self.replace_range(file_id, at, at, text)
+    }
+    
+    /// Deletes the text between two positions and returns it.
+    pub fn delete_range(&mut self, file_id: &str, start: &TextPosition, end: &TextPosition) -> Result<String> {
+        let file = self.read_file(file_id)?;
+        let deleted = file.content.slice(char_range(&file.content, start, end)?).to_string();
+        self.replace_range(file_id, start, end, "")?;
+        Ok(deleted)
+    }
+    
+    /// Replaces the text between two positions with `text`. Only the edited
+    /// range is touched, so small edits stay cheap on large files. Returns
+    /// the new version.
+    pub fn replace_range(&mut self, file_id: &str, start: &TextPosition, end: &TextPosition, text: &str) -> Result<u64> {
+        let file = self.files.get_mut(file_id)
+            .ok_or_else(|| Error::not_found(file_id))?;
+        let range = char_range(&file.content, start, end)?;
+    
+        file.content.remove(range.clone());
+        file.content.insert(range.start, text);
+        Ok(self.content_changed(file_id))
+    }
+    
+    /// Applies LSP-style edits to a file as one change. Every range refers
+    /// to the content before any edit; ranges may touch but not overlap, and
+    /// inserts at the same position keep their order. Nothing is applied
+    /// unless every edit is valid. Returns the new version.
+    pub fn apply_edits(&mut self, file_id: &str, edits: &[TextEdit]) -> Result<u64> {
+        let file = self.files.get_mut(file_id)
+            .ok_or_else(|| Error::not_found(file_id))?;
+    
+        let mut ranges = Vec::with_capacity(edits.len());
+        for edit in edits {
+            let start = TextPosition::LineColumn(edit.range.start.clone());
+            let end = TextPosition::LineColumn(edit.range.end.clone());
+            ranges.push((char_range(&file.content, &start, &end)?, edit.new_text.as_str()));
+        }
+        ranges.sort_by_key(|(range, _)| range.start);
+        if let Some(pair) = ranges.windows(2).find(|pair| pair[0].0.end > pair[1].0.start) {
+            return Err(Error::InvalidArgument(format!(
+                "edits overlap at characters {:?} and {:?}", pair[0].0, pair[1].0
+            )));
+        }
+    
+        // Back to front, so earlier offsets stay valid
+        for (range, text) in ranges.into_iter().rev() {
+            file.content.remove(range.clone());
+            file.content.insert(range.start, text);
+        }
+        Ok(self.content_changed(file_id))
+    }
+    
+    /// The file's revisions, oldest first, without their content.
+    pub fn history(&self, file_id: &str) -> Result<HistoryInfo> {
+        self.read_file(file_id)?;
+        Ok(self.histories.get(file_id)
+            .map(FileHistory::info)
+            .unwrap_or_else(|| FileHistory::default().info()))
+    }
+    
+    /// The revision the file had at `version`, if it is still kept.
+    pub fn revision(&self, file_id: &str, version: u64) -> Result<&Revision> {
+        self.read_file(file_id)?;
+        self.histories.get(file_id)
+            .and_then(|history| history.get(version))
+            .ok_or_else(|| Error::NotFound(format!("version {} of {}", version, file_id)))
+    }
+    
+    /// Writes a kept revision's content back as a new revision. Returns
+    /// the new version.
+    pub fn revert(&mut self, file_id: &str, version: u64) -> Result<u64> {
+        let content = self.revision(file_id, version)?.content.clone();
+        self.files.get_mut(file_id).unwrap().content = content;
+        Ok(self.content_changed(file_id))
+    }
+    
+    /// Steps back to the previous revision. Returns the new version.
+    pub fn undo(&mut self, file_id: &str) -> Result<u64> {
+        self.read_file(file_id)?;
+        let content = self.histories.get_mut(file_id)
+            .and_then(FileHistory::undo)
+            .map(|revision| revision.content.clone())
+            .ok_or_else(|| Error::InvalidArgument(format!("nothing to undo in {}", file_id)))?;
+        self.files.get_mut(file_id).unwrap().content = content;
+        Ok(self.content_restored(file_id))
+    }
+    
+    /// Steps forward to the revision last undone. Returns the new version.
+    pub fn redo(&mut self, file_id: &str) -> Result<u64> {
+        self.read_file(file_id)?;
+        let content = self.histories.get_mut(file_id)
+            .and_then(FileHistory::redo)
+            .map(|revision| revision.content.clone())
+            .ok_or_else(|| Error::InvalidArgument(format!("nothing to redo in {}", file_id)))?;
+        self.files.get_mut(file_id).unwrap().content = content;
+        Ok(self.content_restored(file_id))
+    }
+    
+    /// Bumps the version after undo or redo moved the history, and notes
+    /// it on the revision now current so the version can be read back.
+    fn content_restored(&mut self, file_id: &str) -> u64 {
+        let version = self.bump_version(file_id);
+        if let Some(history) = self.histories.get_mut(file_id) {
+            history.restored_as(version);
+        }
+        version
+    }
+    
+    /// Marks a file's new content as a change and records it as a
+    /// revision. Returns the new version.
+    fn content_changed(&mut self, file_id: &str) -> u64 {
+        let version = self.bump_version(file_id);
+        self.record_revision(file_id);
+        version
+    }
+    
+    fn bump_version(&mut self, file_id: &str) -> u64 {
+        let file = self.files.get_mut(file_id).unwrap();
+        file.modified_at = chrono::Utc::now();
+        file.version += 1;
+        let version = file.version;
+        self.changes += 1;
+    
+        self.notify_change(file_id);
+        version
+    }
+    
+    fn record_revision(&mut self, file_id: &str) {
+        let file = &self.files[file_id];
+        let revision = Revision {
+            version: file.version,
+            restored_as: Vec::new(),
+            author: self.author.clone(),
+            created_at: file.modified_at,
+            content: file.content.clone(),
+        };
+        self.histories.entry(file_id.to_string())
+            .or_default()
+            .record(revision, &self.history_limits);
+    }
+    
+    pub fn delete_file(&mut self, file_id: &str) -> Result<()> {
+        let node_id = self.file_nodes.get(file_id)
+            .cloned()
+            .ok_or_else(|| Error::not_found(file_id))?;
+    
+        // Notify while the file is still in the tree so its path resolves
+        self.notify_change(file_id);
+        self.remove_subtree(&node_id);
+    
+        Ok(())
+    }
+    
+    /// Deletes a directory. Without `recursive` it must be empty; with it,
+    /// every descendant file is removed from the file table as well.
+    pub fn delete_directory(&mut self, path: &Path, recursive: bool) -> Result<DeleteReport> {
+        let path = &normalize_path(path)?;
+        if path.parent().is_none() {
+            return Err(Error::invalid_path(path, "cannot delete the root directory"));
+        }
+        let node = self.node_at(path)?;
+        if !node.is_directory() {
+            return Err(Error::NotADirectory(path.clone()));
+        }
+        if !recursive && !node.children.is_empty() {
+            return Err(Error::DirectoryNotEmpty(path.clone()));
+        }
+    
+        let node_id = node.id.clone();
+        let mut report = DeleteReport::default();
+        for id in self.subtree(&node_id) {
+            let node = &self.nodes[&id];
+            if node.is_directory() {
+                report.removed_directories.push(node.path.clone());
+            } else {
+                report.removed_files.push(node.path.clone());
+            }
+        }
+    
+        for file_id in self.file_ids_in(&node_id) {
+            self.notify_change(&file_id);
+        }
+        self.remove_subtree(&node_id);
+    
+        Ok(report)
+    }
+    
+    /// Applies a unified diff that may create, modify, rename and delete
+    /// several files. Every file is patched in memory first; if anything is
+    /// rejected the VFS is left untouched and the error carries the report.
+    pub fn apply_patch(&mut self, diff: &str, fuzz: usize) -> Result<PatchReport> {
+        // New content per path in patch order; `None` deletes the file
+        let mut staged: Vec<(PathBuf, Option<String>)> = Vec::new();
+        // What to do once every file is checked, in patch order
+        let mut steps = Vec::new();
+        let mut report = PatchReport::default();
+    
+        for file_patch in patch::parse(diff)? {
+            let (action, path) = match (&file_patch.old_path, &file_patch.new_path) {
+                (None, Some(new)) => (PatchAction::Create, new.clone()),
+                (Some(old), None) => (PatchAction::Delete, old.clone()),
+                (Some(old), Some(new)) if old != new => (PatchAction::Rename, new.clone()),
+                (Some(old), _) => (PatchAction::Modify, old.clone()),
+                (None, None) => unreachable!("rejected by the parser"),
+            };
+            let source = file_patch.old_path.as_ref()
+                .map(|old| self.staged_content(&staged, old));
+    
+            let error = match (action, &source) {
+                (_, Some(None)) => Some("file does not exist".to_string()),
+                (PatchAction::Create | PatchAction::Rename, _) => self.check_patch_target(&staged, &path),
+                _ => None,
+            };
+            let (content, hunks) = match error {
+                Some(_) => (None, Vec::new()),
+                None => {
+                    let old = source.flatten().unwrap_or_default();
+                    patch::apply_hunks(&old, &file_patch.hunks, fuzz)
+                }
+            };
+            let error = error.or_else(|| match (&content, action) {
+                (Some(content), PatchAction::Delete) if !content.is_empty() => {
+                    Some("hunks do not remove the whole file".to_string())
+                }
+                _ => None,
+            });
+    
+            if error.is_none() {
+                if let Some(content) = content {
+                    if let Some(old) = file_patch.old_path.as_ref().filter(|old| **old != path) {
+                        stage(&mut staged, old.clone(), None);
+                        steps.push(PatchStep::Move(old.clone(), path.clone()));
+                    }
+                    let content = (action != PatchAction::Delete).then_some(content);
+                    stage(&mut staged, path.clone(), content.clone());
+                    steps.push(PatchStep::Write(path.clone(), content));
+                }
+            }
+            report.files.push(FilePatchReport {
+                renamed_from: file_patch.old_path.filter(|_| action == PatchAction::Rename),
+                path,
+                action,
+                error,
+                hunks,
+            });
+        }
+    
+        if report.is_rejected() {
+            return Err(Error::PatchRejected(Box::new(report)));
+        }
+    
+        for step in steps {
+            let (path, content) = match step {
+                // Renames keep the file's id, version and history
+                PatchStep::Move(from, to) => {
+                    if let Some(parent) = to.parent() {
+                        self.create_directory_all(parent)?;
+                    }
+                    self.move_node(&from, &to)?;
+                    continue;
+                }
+                PatchStep::Write(path, content) => (path, content),
+            };
+            let existing = self.file_id_at(&path).ok().map(str::to_string);
+            match (existing, content) {
+                (Some(file_id), None) => self.delete_file(&file_id)?,
+                (Some(file_id), Some(content)) => {
+                    if self.files[&file_id].content != content.as_str() {
+                        self.write_file(&file_id, content)?;
+                    }
+                }
+                (None, Some(content)) => {
+                    self.create_file_all(&path, content)?;
+                }
+                (None, None) => {}
+            }
+        }
+        Ok(report)
+    }
+    
+    /// Applies operations in order as one change. They run against a copy
+    /// of the VFS, sharing its tables until they change, that replaces it
+    /// only if every one succeeds; change notifications are held back until
+    /// then.
+    pub fn apply_batch(&mut self, operations: &[BatchOperation]) -> Result<BatchReport> {
+        // Snapshots aren't touched by operations, so don't copy them
+        let snapshots = std::mem::take(&mut self.snapshots);
+        let mut staged = self.clone();
+        self.snapshots = snapshots;
+        let changed = Arc::new(Mutex::new(Vec::new()));
+        let recorder = changed.clone();
+        staged.on_change = Some(Arc::new(move |path: &Path| recorder.lock().unwrap().push(path.to_path_buf())));
+    
+        let mut report = BatchReport::default();
+        for operation in operations {
+            match staged.apply_operation(operation) {
+                Ok(outcome) => report.results.push(outcome),
+                Err(e) => {
+                    report.results.fill(OperationOutcome::RolledBack);
+                    let details = e.details();
+                    report.results.push(OperationOutcome::Failed {
+                        kind: e.kind().to_string(),
+                        message: e.to_string(),
+                        details: (!details.is_empty()).then_some(Value::Object(details)),
+                    });
+                    report.results.resize(operations.len(), OperationOutcome::Skipped);
+                    return Err(Error::BatchFailed(Box::new(report)));
+                }
+            }
+        }
+    
+        staged.snapshots = std::mem::take(&mut self.snapshots);
+        staged.on_change = self.on_change.take();
+        *self = staged;
+        if let Some(listener) = &self.on_change {
+            for path in changed.lock().unwrap().iter() {
+                listener(path);
+            }
+        }
+        report.committed = true;
+        Ok(report)
+    }
+    
+    fn apply_operation(&mut self, operation: &BatchOperation) -> Result<OperationOutcome> {
+        let (id, version) = match operation {
+            BatchOperation::Create { path, content, parents } => {
+                let path = Path::new(path);
+                let file_id = if *parents {
+                    self.create_file_all(path, content.clone())?
+                } else {
+                    self.create_file(path, content.clone())?
+                };
+                let version = self.files[&file_id].version;
+                (Some(file_id), Some(version))
+            }
+            BatchOperation::Update { id, content, expected_version } => {
+                let file_id = self.resolve_file(id)?;
+                self.check_version(&file_id, *expected_version)?;
+                self.write_file(&file_id, content.clone())?;
+                let version = self.files[&file_id].version;
+                (Some(file_id), Some(version))
+            }
+            BatchOperation::Delete { path, recursive } => {
+                match self.resolve_file(path) {
+                    Ok(file_id) => self.delete_file(&file_id)?,
+                    Err(Error::IsADirectory(path)) => {
+                        self.delete_directory(&path, *recursive)?;
+                    }
+                    Err(e) => return Err(e),
+                }
+                (None, None)
+            }
+            BatchOperation::Mkdir { path, parents } => {
+                let path = Path::new(path);
+                let directory_id = if *parents {
+                    self.create_directory_all(path)?
+                } else {
+                    self.create_directory(path)?
+                };
+                (Some(directory_id), None)
+            }
+            BatchOperation::Move { from, to } => {
+                let node = self.move_node(Path::new(from), Path::new(to))?;
+                (Some(node.id), None)
+            }
+        };
+        Ok(OperationOutcome::Applied { id, version })
+    }
+    
+    /// A file's content as the patch so far would leave it.
+    fn staged_content(&self, staged: &[(PathBuf, Option<String>)], path: &Path) -> Option<String> {
+        match staged.iter().find(|(staged_path, _)| staged_path == path) {
+            Some((_, content)) => content.clone(),
+            None => self.file_by_path(path).ok().map(|file| file.content.to_string()),
+        }
+    }
+    
+    /// Why a patch can't create a file at `path`, if it can't.
+    fn check_patch_target(&self, staged: &[(PathBuf, Option<String>)], path: &Path) -> Option<String> {
+        let exists = |path: &Path| match staged.iter().find(|(staged_path, _)| staged_path == path) {
+            Some((_, content)) => content.is_some(),
+            None => self.paths.contains_key(path),
+        };
+        if exists(path) {
+            return Some("file already exists".to_string());
+        }
+        path.ancestors()
+            .skip(1)
+            .find(|ancestor| exists(ancestor) && self.node_at(ancestor).map_or(true, |node| !node.is_directory()))
+            .map(|ancestor| format!("{} is a file", ancestor.display()))
+    }
+    
+    /// Diffs one side's content against the other's.
+    pub fn diff(&self, from: &DiffSide, to: &DiffSide, options: &DiffOptions) -> Result<FileDiff> {
+        let (old_path, old) = self.diff_side(from)?;
+        let (new_path, new) = self.diff_side(to)?;
+        Ok(diff::diff_text(&old, &new, &old_path, &new_path, options))
+    }
+    
+    /// The path and content a diff side refers to.
+    fn diff_side(&self, side: &DiffSide) -> Result<(String, String)> {
+        if let Some(name) = &side.snapshot {
+            if side.version.is_some() {
+                return Err(Error::InvalidArgument("a diff side takes a version or a snapshot, not both".to_string()));
+            }
+            let snapshot = self.snapshot(name)?;
+            let file_id = if snapshot.files.contains_key(&side.file) {
+                side.file.clone()
+            } else {
+                snapshot.paths.get(&normalize_path(Path::new(&side.file))?)
+                    .and_then(|node_id| snapshot.nodes[node_id].file_id.clone())
+                    .ok_or_else(|| Error::NotFound(format!("{} in snapshot {}", side.file, name)))?
+            };
+            let path = snapshot.nodes[&snapshot.file_nodes[&file_id]].path.display().to_string();
+            return Ok((path, snapshot.files[&file_id].content.to_string()));
+        }
+    
+        let file_id = self.resolve_file(&side.file)?;
+        let path = self.path_of(&file_id)
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        let content = match side.version {
+            Some(version) => &self.revision(&file_id, version)?.content,
+            None => &self.files[&file_id].content,
+        };
+        Ok((path, content.to_string()))
+    }
+    
+    /// Saves the whole tree and every file's content under `name`.
+    pub fn create_snapshot(&mut self, name: &str) -> Result<SnapshotInfo> {
+        if name.is_empty() {
+            return Err(Error::InvalidArgument("snapshot name is empty".to_string()));
+        }
+        if self.snapshot(name).is_ok() {
+            return Err(Error::Conflict(format!("snapshot {} already exists", name)));
+        }
+    
+        let info = SnapshotInfo {
+            name: name.to_string(),
+            created_at: chrono::Utc::now(),
+            files: self.files.len(),
+        };
+        self.snapshots.push(Snapshot {
+            info: info.clone(),
+            files: self.files.clone(),
+            nodes: self.nodes.clone(),
+            paths: self.paths.clone(),
+            file_nodes: self.file_nodes.clone(),
+        });
+        Ok(info)
+    }
+    
+    pub fn snapshots(&self) -> Vec<SnapshotInfo> {
+        self.snapshots.iter().map(|snapshot| snapshot.info.clone()).collect()
+    }
+    
+    pub fn delete_snapshot(&mut self, name: &str) -> Result<()> {
+        self.snapshot(name)?;
+        self.snapshots.retain(|snapshot| snapshot.info.name != name);
+        Ok(())
+    }
+    
+    /// Diffs a snapshot (old) against the live tree (new), file by file.
+    pub fn diff_snapshot(&self, name: &str, options: &DiffOptions) -> Result<WorkspaceDiff> {
+        let old = self.snapshot(name)?.contents();
+        let new: BTreeMap<&Path, &Rope> = self.files()
+            .into_iter()
+            .map(|(path, file)| (path, &file.content))
+            .collect();
+        let mut paths: Vec<&Path> = old.keys().chain(new.keys()).copied().collect();
+        paths.sort();
+        paths.dedup();
+    
+        let mut diff = WorkspaceDiff::default();
+        for path in paths {
+            let (old_content, new_content) = (old.get(path), new.get(path));
+            if old_content == new_content {
+                continue;
+            }
+            let label = |content: Option<&&Rope>| match content {
+                Some(_) => path.display().to_string(),
+                None => "/dev/null".to_string(),
+            };
+            let file_diff = diff::diff_text(
+                &old_content.map(|content| content.to_string()).unwrap_or_default(),
+                &new_content.map(|content| content.to_string()).unwrap_or_default(),
+                &label(old_content),
+                &label(new_content),
+                options,
+            );
+            if file_diff.hunks.is_empty() {
+                continue;
+            }
+            diff.unified.push_str(&file_diff.unified);
+            diff.files.push(file_diff);
+        }
+        Ok(diff)
+    }
+    
+    /// Puts the tree and contents back as they were in the snapshot. Files
+    /// that still exist keep their ids and get the old content as a new
+    /// revision, so versions only go up and the restore can be undone per
+    /// file.
+    pub fn restore_snapshot(&mut self, name: &str) -> Result<RestoreReport> {
+        let snapshot = self.snapshot(name)?.clone();
+        let mut report = RestoreReport::default();
+    
+        let removed: Vec<String> = self.files.keys()
+            .filter(|file_id| !snapshot.files.contains_key(*file_id))
+            .cloned()
+            .collect();
+        for file_id in removed {
+            if let Some(path) = self.path_of(&file_id) {
+                report.removed.push(path.to_path_buf());
+            }
+            self.notify_change(&file_id);
+            self.files.remove(&file_id);
+            self.histories.remove(&file_id);
+        }
+    
+        // Where kept files are now, so moved ones are announced at both paths
+        let old_paths: BTreeMap<String, PathBuf> = snapshot.files.keys()
+            .filter_map(|file_id| Some((file_id.clone(), self.path_of(file_id)?.to_path_buf())))
+            .collect();
+    
+        self.nodes = snapshot.nodes;
+        self.paths = snapshot.paths;
+        self.file_nodes = snapshot.file_nodes;
+        self.changes += 1;
+    
+        for (file_id, saved) in snapshot.files {
+            let path = self.path_of(&file_id).map(Path::to_path_buf).unwrap_or_default();
+            let Some(file) = self.files.get_mut(&file_id) else {
+                self.files.insert(file_id.clone(), saved);
+                self.record_revision(&file_id);
+                self.notify_change(&file_id);
+                report.recreated.push(path);
+                continue;
+            };
+            file.name = saved.name;
+            file.language = saved.language;
+            let modified = file.content != saved.content;
+            if modified {
+                file.content = saved.content;
+                self.content_changed(&file_id);
+                report.modified.push(path.clone());
+            }
+            if let (Some(old_path), Some(listener)) = (old_paths.get(&file_id).filter(|old| **old != path), &self.on_change) {
+                listener(old_path);
+                if !modified {
+                    listener(&path);
+                }
+            }
+        }
+    
+        report.modified.sort();
+        report.recreated.sort();
+        report.removed.sort();
+        Ok(report)
+    }
+    
+    fn snapshot(&self, name: &str) -> Result<&Snapshot> {
+        self.snapshots.iter()
+            .find(|snapshot| snapshot.info.name == name)
+            .ok_or_else(|| Error::NotFound(format!("snapshot {}", name)))
+    }
+    
+    /// Looks up a file by its absolute path.
+    pub fn file_by_path(&self, path: &Path) -> Result<&VirtualFile> {
+        self.read_file(self.file_id_at(path)?)
+    }
+    
+    /// The id of the file at an absolute path.
+    pub fn file_id_at(&self, path: &Path) -> Result<&str> {
+        let path = &normalize_path(path)?;
+        self.node_at(path)?
+            .file_id
+            .as_deref()
+            .ok_or_else(|| Error::IsADirectory(path.clone()))
+    }
+    
+    /// Resolves either a file id or a path to the file's id, so callers can
+    /// address files whichever way they know them. Anything that isn't an id
+    /// is taken as a path, relative ones from the root as everywhere else.
+    pub fn resolve_file(&self, id_or_path: &str) -> Result<String> {
+        if self.files.contains_key(id_or_path) {
+            return Ok(id_or_path.to_string());
+        }
+        match self.file_id_at(Path::new(id_or_path)) {
+            Ok(file_id) => Ok(file_id.to_string()),
+            Err(Error::NotFound(_)) => Err(Error::not_found(id_or_path)),
+            Err(e) => Err(e),
+        }
+    }
+    
+    /// Every file with its absolute path, in tree order.
+    pub fn files(&self) -> Vec<(&Path, &VirtualFile)> {
+        self.subtree(&self.root_id)
+            .into_iter()
+            .filter_map(|id| {
+                let node = &self.nodes[&id];
+                let file = self.files.get(node.file_id.as_ref()?)?;
+                Some((node.path.as_path(), file))
+            })
+            .collect()
+    }
+    
+    pub fn path_of(&self, file_id: &str) -> Option<&Path> {
+        let node_id = self.file_nodes.get(file_id)?;
+        Some(&self.nodes[node_id].path)
+    }
+    
+    fn notify_change(&self, file_id: &str) {
+        if let (Some(listener), Some(path)) = (&self.on_change, self.path_of(file_id)) {
+            listener(path);
+        }
+    }
+    
+    pub fn create_directory(&mut self, path: &Path) -> Result<String> {
+        let path = normalize_path(path)?;
+        if path.file_name().is_none() {
+            return Err(Error::invalid_path(&path, "missing directory name"));
+        }
+        if self.paths.contains_key(&path) {
+            return Err(Error::AlreadyExists(path));
+        }
+    
+        // Directories are identified by their node id
+        let dir_id = Uuid::new_v4().to_string();
+        self.insert_node(&path, dir_id.clone(), None)?;
+    
+        Ok(dir_id)
+    }
+    
+    /// Like `mkdir -p`: creates `path` and any missing ancestors, and
+    /// succeeds if the directory already exists. Returns the directory's id.
+    pub fn create_directory_all(&mut self, path: &Path) -> Result<String> {
+        let path = normalize_path(path)?;
+        let mut current = PathBuf::from("/");
+        let mut dir_id = self.root_id.clone();
+    
+        for component in path.components().skip(1) {
+            current.push(component);
+            dir_id = match self.node_at(&current) {
+                Ok(node) if node.is_directory() => node.id.clone(),
+                Ok(_) => return Err(Error::NotADirectory(current)),
+                Err(_) => self.create_directory(&current)?,
+            };
+        }
+    
+        Ok(dir_id)
+    }
+    
+    /// Lists a directory's immediate children. Their own `children` are
+    /// left empty, so the cost doesn't depend on what lies below; use `tree`
+    /// for a whole subtree.
+    pub fn list_directory(&self, path: &Path) -> Result<Vec<FileNode>> {
+        let node = self.node_at(&normalize_path(path)?)?;
+    
+        if !node.is_directory() {
+            return Err(Error::NotADirectory(node.path.clone()));
+        }
+    
+        Ok(node.children.iter().map(|id| self.entry(id)).collect())
+    }
+    
+    /// The file or directory at `path` with its whole subtree, as shown in
+    /// the explorer.
+    pub fn tree(&self, path: &Path) -> Result<FileNode> {
+        let node = self.node_at(&normalize_path(path)?)?;
+        Ok(self.view(&node.id))
+    }
+    
+    /// Renames a file or directory in place. `new_name` is a single path
+    /// component.
+    pub fn rename(&mut self, path: &Path, new_name: &str) -> Result<FileNode> {
+        let path = &normalize_path(path)?;
+        if new_name.is_empty() || new_name == "." || new_name == ".." || new_name.contains('/') {
+            return Err(Error::InvalidArgument(format!("invalid name: {:?}", new_name)));
+        }
+        let parent = path.parent()
+            .ok_or_else(|| Error::invalid_path(path, "cannot rename the root directory"))?;
+    
+        self.move_node(path, &parent.join(new_name))
+    }
+    
+    /// Moves a file or directory, with its whole subtree, to `to`. File ids
+    /// and timestamps are kept; a file's language is re-detected when its
+    /// name changes.
+    pub fn move_node(&mut self, from: &Path, to: &Path) -> Result<FileNode> {
+        let (from, to) = (&normalize_path(from)?, &normalize_path(to)?);
+        if from.parent().is_none() {
+            return Err(Error::invalid_path(from, "cannot move the root directory"));
+        }
+        let to_parent = to.parent()
+            .ok_or_else(|| Error::invalid_path(to, "invalid destination"))?;
+        let new_name = to.file_name()
+            .ok_or_else(|| Error::invalid_path(to, "invalid destination"))?
+            .to_string_lossy()
+            .to_string();
+    
+        let node = self.node_at(from)?;
+        if self.paths.contains_key(to) {
+            return Err(Error::AlreadyExists(to.to_path_buf()));
+        }
+        if node.is_directory() && to.starts_with(from) {
+            return Err(Error::invalid_path(to, "cannot move a directory into itself"));
+        }
+        let new_parent = self.node_at(to_parent)?;
+        if !new_parent.is_directory() {
+            return Err(Error::NotADirectory(to_parent.to_path_buf()));
+        }
+        let (node_id, new_parent_id) = (node.id.clone(), new_parent.id.clone());
+    
+        // Files at the old location are gone as far as subscribers can tell
+        for file_id in self.file_ids_in(&node_id) {
+            self.notify_change(&file_id);
+        }
+    
+        self.detach(&node_id);
+        self.nodes.get_mut(&new_parent_id).unwrap().children.push(node_id.clone());
+        let node = self.nodes.get_mut(&node_id).unwrap();
+        node.parent = Some(new_parent_id);
+        let old_name = std::mem::replace(&mut node.name, new_name.clone());
+        self.rebase_paths(&node_id, to.to_path_buf());
+    
+        if new_name != old_name {
+            if let Some(file) = self.nodes[&node_id].file_id.as_ref().and_then(|id| self.files.get_mut(id)) {
+                file.name = new_name.clone();
+                file.language = detect_language(&new_name);
+            }
+        }
+    
+        Ok(self.view(&node_id))
+    }
+    
+    /// Copies a file or directory subtree to `to`, giving every copied node
+    /// and file a fresh id. With `Skip` or `Overwrite`, copying onto an
+    /// existing directory merges into it. Conflicts are checked up front, so
+    /// a failed copy changes nothing.
+    pub fn copy_node(&mut self, from: &Path, to: &Path, policy: ConflictPolicy) -> Result<CopyReport> {
+        let (from, to) = (&normalize_path(from)?, &normalize_path(to)?);
+        let source = self.view(&self.node_at(from)?.id);
+        let to_parent = to.parent()
+            .ok_or_else(|| Error::invalid_path(to, "invalid destination"))?;
+        if !self.node_at(to_parent)?.is_directory() {
+            return Err(Error::NotADirectory(to_parent.to_path_buf()));
+        }
+        if policy == ConflictPolicy::Fail && self.paths.contains_key(to) {
+            return Err(Error::AlreadyExists(to.to_path_buf()));
+        }
+        self.check_copy_target(&source, to)?;
+    
+        let mut report = CopyReport::default();
+        self.copy_into(&source, to, policy, &mut report)?;
+        Ok(report)
+    }
+    
+    /// Fails if a file would land on a directory or vice versa.
+    fn check_copy_target(&self, source: &FileNode, to: &Path) -> Result<()> {
+        let Ok(existing) = self.node_at(to) else {
+            return Ok(());
+        };
+        if existing.is_directory() != source.is_directory {
+            return Err(Error::AlreadyExists(to.to_path_buf()));
+        }
+        for child in &source.children {
+            self.check_copy_target(child, &to.join(&child.name))?;
+        }
+        Ok(())
+    }
+    
+    fn copy_into(&mut self, source: &FileNode, to: &Path, policy: ConflictPolicy, report: &mut CopyReport) -> Result<()> {
+        let existing = self.node_at(to).ok().map(|node| node.file_id.clone());
+    
+        if source.is_directory {
+            if existing.is_none() {
+                self.create_directory(to)?;
+                report.created.push(to.to_path_buf());
+            }
+            for child in &source.children {
+                self.copy_into(child, &to.join(&child.name), policy, report)?;
+            }
+            return Ok(());
+        }
+    
+        let content = source.file_id.as_ref()
+            .and_then(|id| self.files.get(id))
+            .map(|file| file.content.to_string())
+            .unwrap_or_default();
+        match existing {
+            None => {
+                self.create_file(to, content)?;
+                report.created.push(to.to_path_buf());
+            }
+            Some(Some(file_id)) if policy == ConflictPolicy::Overwrite => {
+                self.write_file(&file_id, content)?;
+                report.overwritten.push(to.to_path_buf());
+            }
+            Some(_) => report.skipped.push(to.to_path_buf()),
+        }
+        Ok(())
+    }
+    
+    /// Builds the `FileNode` view of a node and everything below it.
+    fn view(&self, node_id: &str) -> FileNode {
+        let mut view = self.entry(node_id);
+        view.children = self.nodes[node_id].children.iter().map(|id| self.view(id)).collect();
+        view
+    }
+    
+    /// Builds the `FileNode` view of a node alone, without its children.
+    fn entry(&self, node_id: &str) -> FileNode {
+        let node = &self.nodes[node_id];
+        FileNode {
+            id: node.id.clone(),
+            name: node.name.clone(),
+            path: node.path.clone(),
+            is_directory: node.is_directory(),
+            children: Vec::new(),
+            file_id: node.file_id.clone(),
+        }
+    }
+    
+    /// Ids of `node_id` and all its descendants, parents before children.
+    fn subtree(&self, node_id: &str) -> Vec<String> {
+        let mut ids = Vec::new();
+        let mut stack = vec![node_id];
+        while let Some(id) = stack.pop() {
+            ids.push(id.to_string());
+            stack.extend(self.nodes[id].children.iter().rev().map(String::as_str));
+        }
+        ids
+    }
+    
+    /// Ids of every file in a node's subtree, including the node itself.
+    fn file_ids_in(&self, node_id: &str) -> Vec<String> {
+        self.subtree(node_id)
+            .iter()
+            .filter_map(|id| self.nodes[id].file_id.clone())
+            .collect()
+    }
+    
+    fn node_at(&self, path: &Path) -> Result<&Node> {
+        self.paths.get(path)
+            .map(|id| &self.nodes[id])
+            .ok_or_else(|| Error::not_found(path))
+    }
+    
+    /// Adds a node at a normalized path whose parent directory exists.
+    fn insert_node(&mut self, path: &Path, node_id: String, file_id: Option<String>) -> Result<()> {
+        let parent_path = path.parent()
+            .ok_or_else(|| Error::invalid_path(path, "missing parent"))?;
+        let name = path.file_name()
+            .ok_or_else(|| Error::invalid_path(path, "missing name"))?
+            .to_string_lossy()
+            .to_string();
+    
+        let parent = self.node_at(parent_path)?;
+        if !parent.is_directory() {
+            return Err(Error::NotADirectory(parent_path.to_path_buf()));
+        }
+        let parent_id = parent.id.clone();
+    
+        if let Some(file_id) = &file_id {
+            self.file_nodes.insert(file_id.clone(), node_id.clone());
+        }
+        self.paths.insert(path.to_path_buf(), node_id.clone());
+        self.nodes.get_mut(&parent_id).unwrap().children.push(node_id.clone());
+        self.changes += 1;
+        self.nodes.insert(node_id.clone(), Node {
+            id: node_id,
+            name,
+            path: path.to_path_buf(),
+            parent: Some(parent_id),
+            children: vec![],
+            file_id,
+        });
+    
+        Ok(())
+    }
+    
+    /// Unlinks a node from its parent's children.
+    fn detach(&mut self, node_id: &str) {
+        let parent_id = self.nodes[node_id].parent.clone();
+        if let Some(parent) = parent_id.and_then(|id| self.nodes.get_mut(&id)) {
+            parent.children.retain(|child| child != node_id);
+        }
+    }
+    
+    /// Removes a node and its descendants from every table, including the
+    /// files they hold.
+    fn remove_subtree(&mut self, node_id: &str) {
+        self.detach(node_id);
+        self.changes += 1;
+        for id in self.subtree(node_id) {
+            let Some(node) = self.nodes.remove(&id) else {
+                continue;
+            };
+            self.paths.remove(&node.path);
+            if let Some(file_id) = node.file_id {
+                self.file_nodes.remove(&file_id);
+                self.files.remove(&file_id);
+                self.histories.remove(&file_id);
+            }
+        }
+    }
+    
+    /// Points a moved subtree at its new location in the path index.
+    fn rebase_paths(&mut self, node_id: &str, path: PathBuf) {
+        let node = self.nodes.get_mut(node_id).unwrap();
+        let old_path = std::mem::replace(&mut node.path, path.clone());
+        if self.paths.get(&old_path).is_some_and(|id| id == node_id) {
+            self.paths.remove(&old_path);
+        }
+        self.paths.insert(path.clone(), node_id.to_string());
+        self.changes += 1;
+    
+        for child_id in self.nodes[node_id].children.clone() {
+            let child_path = path.join(&self.nodes[&child_id].name);
+            self.rebase_paths(&child_id, child_path);
+        }
+    }
+}
+
+/// Normalizes a VFS path against the single root: `.` is dropped, `..`
+/// steps up (but never above `/`), and relative paths are taken from the
+/// root. Anything else that can't name a VFS node is rejected.
+pub fn normalize_path(path: &Path) -> Result<PathBuf> {
+    let mut normalized = PathBuf::from("/");
+    
+    for component in path.components() {
+        match component {
+            Component::RootDir | Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return Err(Error::invalid_path(path, "escapes the root directory"));
+                }
+            }
+            Component::Normal(name) => {
+                if name.to_string_lossy().contains('\0') {
+                    return Err(Error::invalid_path(path, "contains a NUL byte"));
+                }
+                normalized.push(name);
+            }
+            Component::Prefix(_) => {
+                return Err(Error::invalid_path(path, "drive prefixes are not allowed"));
+            }
+        }
+    }
+    
+    Ok(normalized)
+}
+
+/// A change `apply_patch` makes once the whole patch has been checked.
+enum PatchStep {
+    Move(PathBuf, PathBuf),
+    /// New content for a path; `None` deletes the file
+    Write(PathBuf, Option<String>),
+}
+
+fn stage(staged: &mut Vec<(PathBuf, Option<String>)>, path: PathBuf, content: Option<String>) {
+    match staged.iter_mut().find(|(staged_path, _)| *staged_path == path) {
+        Some(entry) => entry.1 = content,
+        None => staged.push((path, content)),
+    }
+}
+
+/// The character range between two positions, which must be in order.
+fn char_range(content: &Rope, start: &TextPosition, end: &TextPosition) -> Result<std::ops::Range<usize>> {
+    let (start, end) = (char_index(content, start)?, char_index(content, end)?);
+    if start > end {
+        return Err(Error::InvalidArgument(format!("range start {} is after its end {}", start, end)));
+    }
+    Ok(start..end)
+}
+
+/// The character index of `position`, or an error if it's outside `content`.
+/// Lines end only at `\n`, `\r\n` or `\r`, as in LSP.
+pub(crate) fn char_index(content: &Rope, position: &TextPosition) -> Result<usize> {
+    match position {
+        TextPosition::Offset { offset } => {
+            if *offset > content.len_utf16_cu() {
+                return Err(Error::InvalidArgument(format!(
+                    "offset {} is past the end of the file ({} UTF-16 code units)", offset, content.len_utf16_cu()
+                )));
+            }
+            let char = content.utf16_cu_to_char(*offset);
+            if content.char_to_utf16_cu(char) != *offset {
+                return Err(Error::InvalidArgument(format!("offset {} is inside a character", offset)));
+            }
+            Ok(char)
+        }
+        TextPosition::LineColumn(Position { line, column }) => {
+            let (line, column) = (*line as usize, *column as usize);
+            if line >= content.len_lines() {
+                return Err(Error::InvalidArgument(format!(
+                    "line {} is past the end of the file ({} lines)", line, content.len_lines()
+                )));
+            }
+            let text = content.line(line);
+            let ending = match (text.len_chars().checked_sub(2).map(|i| text.char(i)), text.chars().last()) {
+                (Some('\r'), Some('\n')) => 2,
+                (_, Some('\n' | '\r')) => 1,
+                _ => 0,
+            };
+            if column > text.len_utf16_cu() - ending {
+                return Err(Error::InvalidArgument(format!("column {} is past the end of line {}", column, line)));
+            }
+            let char = text.utf16_cu_to_char(column);
+            if text.char_to_utf16_cu(char) != column {
+                return Err(Error::InvalidArgument(format!("column {} of line {} is inside a character", column, line)));
+            }
+            Ok(content.line_to_char(line) + char)
+        }
+    }
+}
+
+/// Serde adapter that stores a `Rope` as a plain string.
+pub(crate) mod rope_text {
+    use ropey::Rope;
+    use serde::{Deserialize, Deserializer, Serializer};
+    
+    pub fn serialize<S: Serializer>(content: &Rope, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(content)
+    }
+    
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rope, D::Error> {
+        String::deserialize(deserializer).map(Rope::from)
+    }
+}
+
+fn detect_language(filename: &str) -> Option<String> {
+    let extension = Path::new(filename)
+        .extension()
+        .and_then(|ext| ext.to_str())?;
+    
+    match extension {
+        "js" | "jsx" => Some("javascript".to_string()),
+        "ts" | "tsx" => Some("typescript".to_string()),
+        "rs" => Some("rust".to_string()),
+        "py" => Some("python".to_string()),
+        "json" => Some("json".to_string()),
+        "html" => Some("html".to_string()),
+        "css" => Some("css".to_string()),
+        "md" => Some("markdown".to_string()),
+        _ => None,
+    }
+}
\ No newline at end of file
@@ -0,0 +1,198 @@
+//! Saving the VFS to a workspace file on disk and loading it back. The file
+//! is versioned JSON holding the tree, file contents and metadata; revision
+//! histories and snapshots only live in memory.
+
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Context;
+use serde::{Serialize, Deserialize};
+use tempfile::NamedTempFile;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use super::vfs::{VirtualFile, VirtualFileSystem};
+use super::AppState;
+use crate::error::{Error, Result};
+
+/// Version of the workspace file format, bumped on incompatible changes.
+pub const WORKSPACE_FORMAT: u32 = 1;
+
+/// Name of the workspace file inside an app data directory.
+pub const WORKSPACE_FILE_NAME: &str = "workspace.json";
+
+/// Shortest pause between autosave checks, whatever `auto_save_delay` says.
+const MIN_AUTO_SAVE_DELAY: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Workspace {
+    pub format: u32,
+    pub saved_at: chrono::DateTime<chrono::Utc>,
+    pub root_id: String,
+    /// Every node but the root, parents before children
+    pub nodes: Vec<SavedNode>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedNode {
+    pub id: String,
+    pub path: PathBuf,
+    /// The file held by the node; directories have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<VirtualFile>,
+}
+
+/// Where and when a workspace was saved.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SaveReport {
+    pub path: PathBuf,
+    pub saved_at: chrono::DateTime<chrono::Utc>,
+    pub files: usize,
+}
+
+/// Read before the rest of the file, so a newer format is reported as such
+/// rather than as whatever field it fails to parse.
+#[derive(Deserialize)]
+struct FormatHeader {
+    format: u32,
+}
+
+/// Writes the workspace to `path`. The data goes to a temporary file in the
+/// same directory, is flushed to disk and then renamed over `path`, so a
+/// crash mid-save leaves the previous file intact.
+pub fn write(workspace: &Workspace, path: &Path) -> Result<()> {
+    let dir = path.parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("creating {}", dir.display()))?;
+
+    let mut temp = NamedTempFile::new_in(dir)
+        .with_context(|| format!("creating a temporary file in {}", dir.display()))?;
+    let mut writer = BufWriter::new(temp.as_file_mut());
+    serde_json::to_writer(&mut writer, workspace).context("serializing the workspace")?;
+    writer.flush().context("writing the workspace")?;
+    drop(writer);
+    temp.as_file().sync_all().context("flushing the workspace to disk")?;
+    temp.persist(path)
+        .with_context(|| format!("replacing {}", path.display()))?;
+
+    // Make the rename itself durable
+    #[cfg(unix)]
+    std::fs::File::open(dir).and_then(|dir| dir.sync_all())
+        .with_context(|| format!("flushing {}", dir.display()))?;
+
+    Ok(())
+}
+
+/// Saves the VFS to `path`.
+pub fn save(vfs: &VirtualFileSystem, path: &Path) -> Result<()> {
+    write(&vfs.to_workspace(), path)
+}
+
+/// Loads the workspace file at `path`, or `None` if there isn't one.
+pub fn load(path: &Path) -> Result<Option<VirtualFileSystem>> {
+    match read(path)? {
+        Some(bytes) => parse(path, &bytes).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Loads the workspace file at `path` on launch, or an empty VFS if there
+/// isn't one. A file that can't be parsed is moved aside to `<name>.corrupt`,
+/// so the next save doesn't overwrite it, and an empty VFS is used instead.
+/// Errors reading or moving the file, such as a denied permission, are
+/// returned instead.
+pub fn restore(path: &Path) -> Result<VirtualFileSystem> {
+    let Some(bytes) = read(path)? else {
+        return Ok(VirtualFileSystem::new());
+    };
+
+    match parse(path, &bytes) {
+        Ok(vfs) => {
+            info!("Restored workspace from {}", path.display());
+            Ok(vfs)
+        }
+        Err(e) => {
+            let mut aside = path.as_os_str().to_owned();
+            aside.push(".corrupt");
+            error!("Failed to load workspace {}: {}; moving it to {}", path.display(), e, Path::new(&aside).display());
+            std::fs::rename(path, &aside)
+                .with_context(|| format!("moving {} aside", path.display()))?;
+            Ok(VirtualFileSystem::new())
+        }
+    }
+}
+
+/// The bytes of the file at `path`, or `None` if there isn't one.
+fn read(path: &Path) -> Result<Option<Vec<u8>>> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(anyhow::Error::new(e).context(format!("reading {}", path.display())).into()),
+    }
+}
+
+/// Builds a VFS from the contents of a workspace file.
+fn parse(path: &Path, bytes: &[u8]) -> Result<VirtualFileSystem> {
+    let header: FormatHeader = serde_json::from_slice(bytes)
+        .with_context(|| format!("{} is not a workspace file", path.display()))?;
+    if header.format != WORKSPACE_FORMAT {
+        return Err(Error::InvalidArgument(format!(
+            "{} has workspace format {}, but only format {} is supported",
+            path.display(), header.format, WORKSPACE_FORMAT
+        )));
+    }
+    let workspace: Workspace = serde_json::from_slice(bytes)
+        .with_context(|| format!("parsing {}", path.display()))?;
+
+    VirtualFileSystem::from_workspace(workspace)
+}
+
+/// Saves the VFS to the configured workspace file.
+pub async fn save_state(state: &AppState) -> Result<SaveReport> {
+    let path = state.config.read().await.workspace.path.clone()
+        .ok_or_else(|| Error::InvalidArgument("no workspace file is configured".to_string()))?;
+    let workspace = state.vfs.read().await.to_workspace();
+    let report = SaveReport {
+        path: path.clone(),
+        saved_at: workspace.saved_at,
+        files: workspace.nodes.iter().filter(|node| node.file.is_some()).count(),
+    };
+
+    tokio::task::spawn_blocking(move || write(&workspace, &path))
+        .await
+        .context("workspace save task failed")??;
+    Ok(report)
+}
+
+/// Saves the VFS every `editor.auto_save_delay` milliseconds while there
+/// are unsaved changes and `editor.auto_save` is on. Both settings and the
+/// workspace path are re-read each time, so changes apply without a restart.
+pub fn spawn_autosave(state: AppState) -> JoinHandle<()> {
+    // Taken now rather than once the task first runs, which may be after
+    // further changes. If the VFS is locked, the first check just saves.
+    let mut saved = state.vfs.try_read().map_or(0, |vfs| vfs.changes());
+    tokio::spawn(async move {
+        loop {
+            let delay = state.config.read().await.editor.auto_save_delay;
+            tokio::time::sleep(Duration::from_millis(delay.into()).max(MIN_AUTO_SAVE_DELAY)).await;
+
+            let config = state.config.read().await;
+            if !config.editor.auto_save || config.workspace.path.is_none() {
+                continue;
+            }
+            drop(config);
+
+            let changes = state.vfs.read().await.changes();
+            if changes == saved {
+                continue;
+            }
+            match save_state(&state).await {
+                Ok(_) => saved = changes,
+                Err(e) => warn!("Autosave failed: {}", e),
+            }
+        }
+    })
+}
@@ -0,0 +1,24 @@
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, filter::EnvFilter, fmt::MakeWriter};
+
+pub fn init_logging() {
+    init_logging_with(std::io::stdout);
+}
+
+/// Logs to stderr, for the stdio MCP transport where stdout carries protocol
+/// messages.
+pub fn init_stderr_logging() {
+    init_logging_with(std::io::stderr);
+}
+
+fn init_logging_with<W>(writer: W)
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    tracing_subscriber::registry()
+        .with(
+            EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "abide_core=debug,abide_server=debug,tower_http=debug,axum=debug".into()),
+        )
+        .with(tracing_subscriber::fmt::layer().with_writer(writer))
+        .init();
+}
\ No newline at end of file
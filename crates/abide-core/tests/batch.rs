@@ -0,0 +1,142 @@
+//! All-or-nothing batches of VFS operations.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use abide_core::api;
+use abide_core::mcp::{handle_mcp_request, McpRequest, McpResponse};
+use abide_core::state::batch::{BatchOperation, OperationOutcome};
+use abide_core::{AppState, Error, VirtualFileSystem};
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+fn operations(value: Value) -> Vec<BatchOperation> {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn batch_applies_every_operation_in_order() {
+    let mut vfs = VirtualFileSystem::new();
+    vfs.create_file_all(Path::new("/src/old.rs"), "old".to_string()).unwrap();
+    vfs.create_file_all(Path::new("/src/main.rs"), "fn main() {}".to_string()).unwrap();
+    let changed = Arc::new(Mutex::new(Vec::new()));
+    let recorder = changed.clone();
+    vfs.set_change_listener(Arc::new(move |path: &Path| recorder.lock().unwrap().push(path.to_path_buf())));
+
+    let report = vfs.apply_batch(&operations(json!([
+        { "op": "mkdir", "path": "/src/util" },
+        { "op": "create", "path": "/src/util/mod.rs", "content": "pub mod fmt;" },
+        { "op": "create", "path": "/tests/it.rs", "parents": true },
+        { "op": "update", "path": "/src/main.rs", "content": "mod util;\nfn main() {}" },
+        { "op": "move", "from": "/src/util", "to": "/src/helpers" },
+        { "op": "delete", "path": "/src/old.rs" },
+    ]))).unwrap();
+
+    assert!(report.committed);
+    assert!(report.results.iter().all(|result| matches!(result, OperationOutcome::Applied { .. })));
+    assert!(matches!(&report.results[3], OperationOutcome::Applied { version: Some(2), .. }));
+    assert_eq!(vfs.file_by_path(Path::new("/src/helpers/mod.rs")).unwrap().content, "pub mod fmt;");
+    assert!(vfs.resolve_file("/tests/it.rs").is_ok());
+    assert!(vfs.resolve_file("/src/old.rs").is_err());
+
+    // Notifications arrive once the batch is kept
+    let changed = changed.lock().unwrap();
+    assert!(changed.contains(&Path::new("/src/main.rs").to_path_buf()));
+    assert!(changed.contains(&Path::new("/src/old.rs").to_path_buf()));
+}
+
+#[test]
+fn a_failed_operation_rolls_back_the_whole_batch() {
+    let mut vfs = VirtualFileSystem::new();
+    let main = vfs.create_file(Path::new("/main.rs"), "fn main() {}".to_string()).unwrap();
+    vfs.create_snapshot("kept").unwrap();
+    let notified = Arc::new(Mutex::new(0));
+    let counter = notified.clone();
+    vfs.set_change_listener(Arc::new(move |_: &Path| *counter.lock().unwrap() += 1));
+
+    let result = vfs.apply_batch(&operations(json!([
+        { "op": "create", "path": "/a.rs", "content": "a" },
+        { "op": "update", "id": main, "content": "changed" },
+        { "op": "delete", "path": "/missing.rs" },
+        { "op": "mkdir", "path": "/never" },
+    ])));
+
+    let Err(Error::BatchFailed(report)) = result else {
+        panic!("batch should fail");
+    };
+    assert!(!report.committed);
+    assert_eq!(report.results[..2], [OperationOutcome::RolledBack, OperationOutcome::RolledBack]);
+    assert!(matches!(&report.results[2], OperationOutcome::Failed { kind, .. } if kind == "not_found"));
+    assert_eq!(report.results[3], OperationOutcome::Skipped);
+
+    assert!(vfs.resolve_file("/a.rs").is_err());
+    assert_eq!(vfs.read_file(&main).unwrap().content, "fn main() {}");
+    assert_eq!(vfs.read_file(&main).unwrap().version, 1);
+    assert_eq!(*notified.lock().unwrap(), 0);
+    assert_eq!(vfs.snapshots().len(), 1);
+}
+
+#[test]
+fn failed_operations_keep_the_error_details() {
+    let mut vfs = VirtualFileSystem::new();
+    let main = vfs.create_file(Path::new("/main.rs"), "fn main() {}".to_string()).unwrap();
+    vfs.write_file(&main, "fn main() { run() }".to_string()).unwrap();
+
+    let result = vfs.apply_batch(&operations(json!([
+        { "op": "update", "id": main, "content": "stale", "expected_version": 1 },
+    ])));
+
+    let Err(Error::BatchFailed(report)) = result else {
+        panic!("batch should fail");
+    };
+    let OperationOutcome::Failed { kind, details: Some(details), .. } = &report.results[0] else {
+        panic!("unexpected outcome: {:?}", report.results[0]);
+    };
+    assert_eq!(kind, "version_conflict");
+    assert_eq!(details["current"], 2);
+    assert_eq!(details["content"], "fn main() { run() }");
+    assert_eq!(serde_json::to_value(&report.results[0]).unwrap()["details"]["expected"], 1);
+}
+
+#[tokio::test]
+async fn batch_over_mcp_and_rest() {
+    let state = Arc::new(AppState::new(VirtualFileSystem::new()));
+
+    let request: McpRequest = serde_json::from_value(json!({
+        "type": "batch",
+        "operations": [
+            { "op": "create", "path": "/a.txt", "content": "a" },
+            { "op": "create", "path": "/a.txt", "content": "again" },
+        ]
+    })).unwrap();
+    match handle_mcp_request(request, &state).await {
+        McpResponse::Error { code, data, .. } => {
+            assert_eq!(code, -32022);
+            assert_eq!(data["report"]["results"][1]["kind"], "already_exists");
+        }
+        other => panic!("unexpected response: {:?}", other),
+    }
+
+    let response = api::router((*state).clone())
+        .oneshot(
+            Request::post("/api/batch")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({
+                    "operations": [
+                        { "op": "mkdir", "path": "/docs" },
+                        { "op": "create", "path": "/docs/a.md", "content": "# A" },
+                    ]
+                }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["committed"], true);
+    assert_eq!(body["results"][1]["status"], "applied");
+    assert!(state.vfs.read().await.resolve_file("/docs/a.md").is_ok());
+}
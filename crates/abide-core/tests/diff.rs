@@ -0,0 +1,137 @@
+//! Diffs between files with `file.diff`.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use abide_core::api;
+use abide_core::mcp::{handle_mcp_request, McpRequest, McpResponse};
+use abide_core::state::diff::{diff_text, DiffOptions, DiffSide, LineKind, Whitespace};
+use abide_core::state::patch::DEFAULT_FUZZ;
+use abide_core::{AppState, Error, VirtualFileSystem};
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+const OLD: &str = "fn main() {\n    let x = 1;\n    println!(\"{}\", x);\n}\n";
+const NEW: &str = "fn main() {\n    let x = 2;\n    println!(\"{}\", x);\n}\n// end";
+
+fn side(file: &str) -> DiffSide {
+    DiffSide { file: file.to_string(), version: None, snapshot: None }
+}
+
+fn content(vfs: &VirtualFileSystem, path: &str) -> String {
+    vfs.file_by_path(Path::new(path)).unwrap().content.to_string()
+}
+
+#[test]
+fn unified_text_and_hunks_describe_the_change() {
+    let diff = diff_text(OLD, NEW, "/src/main.rs", "/src/main.rs", &DiffOptions::default());
+    assert_eq!(diff.unified, "\
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,4 +1,5 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
+     println!(\"{}\", x);
+ }
++// end
+\\ No newline at end of file
+");
+    assert_eq!((diff.added, diff.removed), (2, 1));
+
+    let hunk = &diff.hunks[0];
+    assert_eq!((hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines), (1, 4, 1, 5));
+    assert_eq!(hunk.lines[1].kind, LineKind::Remove);
+    assert_eq!((hunk.lines[1].old_line, hunk.lines[1].new_line), (Some(2), None));
+    assert_eq!(hunk.lines[2].kind, LineKind::Add);
+    assert_eq!((hunk.lines[2].old_line, hunk.lines[2].new_line), (None, Some(2)));
+
+    let same = diff_text(OLD, OLD, "/a", "/a", &DiffOptions::default());
+    assert!(same.unified.is_empty() && same.hunks.is_empty());
+}
+
+#[test]
+fn whitespace_sensitivity_is_configurable() {
+    let old = "a  b\nc\n";
+    let new = "a b  \nc\n";
+    let options = |whitespace| DiffOptions { whitespace, context: None };
+
+    assert_eq!(diff_text(old, new, "/f", "/f", &options(Whitespace::Exact)).hunks.len(), 1);
+    assert_eq!(diff_text(old, new, "/f", "/f", &options(Whitespace::IgnoreTrailing)).hunks.len(), 1);
+    assert!(diff_text(old, new, "/f", "/f", &options(Whitespace::IgnoreAll)).hunks.is_empty());
+    assert!(diff_text("a\n", "a  \n", "/f", "/f", &options(Whitespace::IgnoreTrailing)).hunks.is_empty());
+
+    // Like `diff -w`, whitespace added where there was none is ignored too
+    assert!(diff_text("a b\n", "\tab\n", "/f", "/f", &options(Whitespace::IgnoreAll)).hunks.is_empty());
+    assert_eq!(diff_text("a b\n", "a c\n", "/f", "/f", &options(Whitespace::IgnoreAll)).hunks.len(), 1);
+}
+
+#[test]
+fn context_splits_distant_changes_into_hunks() {
+    let old: String = (1..=20).map(|n| format!("line {n}\n")).collect();
+    let new = old.replace("line 2\n", "two\n").replace("line 19\n", "nineteen\n");
+
+    assert_eq!(diff_text(&old, &new, "/f", "/f", &DiffOptions::default()).hunks.len(), 2);
+    let wide = DiffOptions { context: Some(10), ..Default::default() };
+    assert_eq!(diff_text(&old, &new, "/f", "/f", &wide).hunks.len(), 1);
+    let tight = DiffOptions { context: Some(0), ..Default::default() };
+    assert_eq!(diff_text(&old, &new, "/f", "/f", &tight).hunks[0].lines.len(), 2);
+}
+
+#[test]
+fn diff_applies_back_as_a_patch() {
+    let mut vfs = VirtualFileSystem::new();
+    vfs.create_file_all(Path::new("/src/main.rs"), OLD.to_string()).unwrap();
+    vfs.create_file_all(Path::new("/src/next.rs"), NEW.to_string()).unwrap();
+
+    let diff = vfs.diff(&side("/src/main.rs"), &side("/src/next.rs"), &DiffOptions::default()).unwrap();
+    assert_eq!((diff.old_path.as_str(), diff.new_path.as_str()), ("/src/main.rs", "/src/next.rs"));
+
+    // Same path on both sides so the patch modifies in place
+    let patch = diff.unified.replace("b/src/next.rs", "b/src/main.rs");
+    vfs.apply_patch(&patch, DEFAULT_FUZZ).unwrap();
+    assert_eq!(content(&vfs, "/src/main.rs"), NEW);
+
+    // A last line that only gains its newline is still a change
+    let ending = diff_text("a", "a\n", "/f", "/f", &DiffOptions::default());
+    assert_eq!((ending.added, ending.removed), (1, 1));
+
+    let missing = vfs.diff(&side("/src/nope.rs"), &side("/src/main.rs"), &DiffOptions::default());
+    assert!(matches!(missing, Err(Error::NotFound(_))));
+}
+
+#[tokio::test]
+async fn diff_over_mcp_and_rest() {
+    let state = Arc::new(AppState::new(VirtualFileSystem::new()));
+    {
+        let mut vfs = state.vfs.write().await;
+        vfs.create_file(Path::new("/a.txt"), "one\ntwo\n".to_string()).unwrap();
+        vfs.create_file(Path::new("/b.txt"), "one\n  two\n".to_string()).unwrap();
+    }
+
+    let request: McpRequest = serde_json::from_value(json!({
+        "type": "file.diff", "from": { "file": "/a.txt" }, "to": { "file": "/b.txt" }
+    })).unwrap();
+    match handle_mcp_request(request, &state).await {
+        McpResponse::Success { data } => assert_eq!(data["added"], 1),
+        other => panic!("unexpected response: {:?}", other),
+    }
+
+    let response = api::router((*state).clone())
+        .oneshot(
+            Request::post("/api/diff")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({
+                    "from": { "file": "/a.txt" }, "to": { "file": "/b.txt" }, "whitespace": "ignore_all"
+                }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["unified"], "");
+}
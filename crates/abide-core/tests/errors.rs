@@ -0,0 +1,129 @@
+//! One error type, mapped the same way onto REST, JSON-RPC and Tauri.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use abide_core::api;
+use abide_core::mcp::jsonrpc::{JsonRpcError, RESOURCE_NOT_FOUND, SERVER_NOT_INITIALIZED};
+use abide_core::mcp::operations::{DirectoryDeleteParams, FileReadParams};
+use abide_core::mcp::{handle_mcp_request, McpRequest, McpResponse};
+use abide_core::{AppState, Error, VirtualFileSystem};
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+#[test]
+fn vfs_reports_typed_errors() {
+    let mut vfs = VirtualFileSystem::new();
+    vfs.create_directory(Path::new("/src")).unwrap();
+    vfs.create_file(Path::new("/src/main.js"), String::new()).unwrap();
+
+    assert!(matches!(vfs.resolve_file("/missing.js"), Err(Error::NotFound(_))));
+    assert!(matches!(vfs.resolve_file("/src"), Err(Error::IsADirectory(_))));
+    assert!(matches!(vfs.list_directory(Path::new("/src/main.js")), Err(Error::NotADirectory(_))));
+    assert!(matches!(vfs.delete_directory(Path::new("/src"), false), Err(Error::DirectoryNotEmpty(_))));
+    assert!(matches!(vfs.create_file(Path::new("/../x.js"), String::new()), Err(Error::InvalidPath { .. })));
+}
+
+#[test]
+fn errors_map_to_status_code_and_data() {
+    let error = Error::AlreadyExists("/src/main.js".into());
+    assert_eq!(error.http_status(), StatusCode::CONFLICT);
+    assert_eq!(error.jsonrpc_code(), -32010);
+    assert_eq!(error.data(), json!({ "kind": "already_exists", "path": "/src/main.js" }));
+
+    let rpc = JsonRpcError::from(Error::invalid_path("/../x", "escapes the root directory"));
+    assert_eq!(rpc.code, -32014);
+    assert_eq!(rpc.data.unwrap()["reason"], "escapes the root directory");
+
+    // Tauri commands reject with the serialized error.
+    let tauri = serde_json::to_value(Error::not_found("/a.js")).unwrap();
+    assert_eq!(tauri["kind"], "not_found");
+    assert_eq!(tauri["target"], "/a.js");
+    assert_eq!(tauri["message"], "Not found: /a.js");
+
+    assert_eq!(Error::Conflict("stale".into()).jsonrpc_code(), -32020);
+
+    // Not-found can't be mistaken for the protocol's own errors
+    assert_ne!(Error::not_found("/a.js").jsonrpc_code(), SERVER_NOT_INITIALIZED);
+    assert_ne!(Error::not_found("/a.js").jsonrpc_code(), RESOURCE_NOT_FOUND);
+}
+
+#[tokio::test]
+async fn mcp_tool_errors_carry_code_and_data() {
+    let state = Arc::new(AppState::new(VirtualFileSystem::new()));
+
+    match handle_mcp_request(McpRequest::FileRead(FileReadParams { id: "/a.js".to_string() }), &state).await {
+        McpResponse::Error { code, data, .. } => {
+            assert_eq!(code, -32001);
+            assert_eq!(data["kind"], "not_found");
+        }
+        other => panic!("unexpected response: {:?}", other),
+    }
+
+    state.vfs.write().await.create_file_all(Path::new("/src/a.js"), String::new()).unwrap();
+    let delete = McpRequest::DirectoryDelete(DirectoryDeleteParams { path: "/src".to_string(), recursive: false });
+    match handle_mcp_request(delete, &state).await {
+        McpResponse::Error { code, data, .. } => {
+            assert_eq!(code, -32013);
+            assert_eq!(data, json!({ "kind": "directory_not_empty", "path": "/src" }));
+        }
+        other => panic!("unexpected response: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn rest_errors_use_status_and_kind() {
+    let state = AppState::new(VirtualFileSystem::new());
+    state.vfs.write().await.create_file(Path::new("/a.js"), String::new()).unwrap();
+
+    let missing = api::router(state.clone())
+        .oneshot(Request::get("/api/files/missing.js").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+    let bytes = axum::body::to_bytes(missing.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["kind"], "not_found");
+    assert!(body["error"].as_str().unwrap().contains("missing.js"));
+
+    let duplicate = api::router(state.clone())
+        .oneshot(
+            Request::post("/api/files")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({ "path": "/a.js", "content": "" }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(duplicate.status(), StatusCode::CONFLICT);
+    let bytes = axum::body::to_bytes(duplicate.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["kind"], "already_exists");
+    assert_eq!(body["path"], "/a.js");
+
+    let malformed = api::router(state.clone())
+        .oneshot(
+            Request::post("/api/files")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{ "path": "/b.js" }"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(malformed.status(), StatusCode::BAD_REQUEST);
+    let bytes = axum::body::to_bytes(malformed.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["kind"], "invalid_argument");
+    assert!(body["error"].as_str().unwrap().contains("content"));
+
+    let bad_query = api::router(state.clone())
+        .oneshot(Request::delete("/api/directories/src?recursive=maybe").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(bad_query.status(), StatusCode::BAD_REQUEST);
+    let bytes = axum::body::to_bytes(bad_query.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["kind"], "invalid_argument");
+}
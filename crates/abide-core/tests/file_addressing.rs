@@ -0,0 +1,71 @@
+//! Files can be addressed by id or by path on every API surface.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use abide_core::api;
+use abide_core::mcp::operations::{FileReadParams, FileUpdateParams};
+use abide_core::mcp::{handle_mcp_request, McpRequest, McpResponse};
+use abide_core::{AppState, Error, VirtualFileSystem};
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use serde_json::Value;
+use tower::ServiceExt;
+
+#[test]
+fn vfs_resolves_ids_and_paths() {
+    let mut vfs = VirtualFileSystem::new();
+    vfs.create_directory(Path::new("/src")).unwrap();
+    let file_id = vfs.create_file(Path::new("/src/main.js"), String::new()).unwrap();
+
+    assert_eq!(vfs.resolve_file(&file_id).unwrap(), file_id);
+    assert_eq!(vfs.resolve_file("/src/main.js").unwrap(), file_id);
+    assert_eq!(vfs.resolve_file("src/main.js").unwrap(), file_id);
+    assert_eq!(vfs.resolve_file("./src/../src/main.js").unwrap(), file_id);
+    assert!(vfs.resolve_file("/src").is_err());
+    assert!(vfs.resolve_file("/src/missing.js").is_err());
+    assert!(matches!(vfs.resolve_file("not-an-id"), Err(Error::NotFound(target)) if target == "not-an-id"));
+}
+
+#[tokio::test]
+async fn mcp_file_operations_accept_paths() {
+    let state = Arc::new(AppState::new(VirtualFileSystem::new()));
+    state.vfs.write().await.create_file(Path::new("/a.js"), "1".to_string()).unwrap();
+
+    let update = McpRequest::FileUpdate(FileUpdateParams {
+        id: "/a.js".to_string(),
+        content: "2".to_string(),
+        expected_version: None,
+    });
+    assert!(matches!(handle_mcp_request(update, &state).await, McpResponse::Success { .. }));
+
+    match handle_mcp_request(McpRequest::FileRead(FileReadParams { id: "/a.js".to_string() }), &state).await {
+        McpResponse::Success { data } => assert_eq!(data["content"], "2"),
+        other => panic!("unexpected response: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn rest_file_routes_accept_paths() {
+    let state = AppState::new(VirtualFileSystem::new());
+    state.vfs.write().await.create_directory(Path::new("/src")).unwrap();
+    let file_id = state.vfs.write().await.create_file(Path::new("/src/main.js"), "1".to_string()).unwrap();
+
+    let read = |uri: &str| Request::get(uri).body(Body::empty()).unwrap();
+
+    let by_path = api::router(state.clone()).oneshot(read("/api/files/src/main.js")).await.unwrap();
+    assert_eq!(by_path.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(by_path.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["id"], file_id.as_str());
+
+    let by_id = api::router(state.clone()).oneshot(read(&format!("/api/files/{}", file_id))).await.unwrap();
+    assert_eq!(by_id.status(), StatusCode::OK);
+
+    let deleted = api::router(state.clone())
+        .oneshot(Request::delete("/api/files/src/main.js").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(deleted.status(), StatusCode::NO_CONTENT);
+    assert!(state.vfs.read().await.resolve_file(&file_id).is_err());
+}
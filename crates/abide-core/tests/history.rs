@@ -0,0 +1,172 @@
+//! Per-file revision history with undo, redo and revert.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use abide_core::api;
+use abide_core::mcp::{handle_mcp_request_as, McpRequest, McpResponse};
+use abide_core::state::config::HistoryConfig;
+use abide_core::state::history::AuthorKind;
+use abide_core::state::Author;
+use abide_core::{AppConfig, AppState, Error, VirtualFileSystem};
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+fn content(vfs: &VirtualFileSystem, file_id: &str) -> String {
+    vfs.read_file(file_id).unwrap().content.to_string()
+}
+
+#[test]
+fn undo_and_redo_step_through_revisions() {
+    let mut vfs = VirtualFileSystem::new();
+    let file_id = vfs.create_file(Path::new("/a.txt"), "one".to_string()).unwrap();
+    vfs.write_file(&file_id, "two".to_string()).unwrap();
+    vfs.set_author(Author::agent(Some("session-1")));
+    vfs.write_file(&file_id, "three".to_string()).unwrap();
+
+    let history = vfs.history(&file_id).unwrap();
+    let versions: Vec<_> = history.revisions.iter().map(|revision| revision.version).collect();
+    assert_eq!(versions, [1, 2, 3]);
+    assert_eq!(history.revisions[0].author, Author::user());
+    assert_eq!(history.revisions[2].author.session_id.as_deref(), Some("session-1"));
+    assert!(history.revisions[2].current && history.can_undo && !history.can_redo);
+
+    // Undo and redo still move the version forward, and every version
+    // they return reads back as the content it stands for
+    assert_eq!(vfs.undo(&file_id).unwrap(), 4);
+    assert_eq!(vfs.undo(&file_id).unwrap(), 5);
+    assert_eq!(content(&vfs, &file_id), "one");
+    assert!(matches!(vfs.undo(&file_id), Err(Error::InvalidArgument(_))));
+    assert_eq!(vfs.redo(&file_id).unwrap(), 6);
+    assert_eq!(content(&vfs, &file_id), "two");
+    assert_eq!(vfs.revision(&file_id, 4).unwrap().content, "two");
+    assert_eq!(vfs.revision(&file_id, 5).unwrap().content, "one");
+    assert_eq!(vfs.revision(&file_id, 6).unwrap().content, "two");
+    let history = vfs.history(&file_id).unwrap();
+    assert!(history.revisions[1].current);
+    assert_eq!(history.revisions[1].restored_as, [4, 6]);
+
+    // A new change drops what could have been redone
+    vfs.write_file(&file_id, "four".to_string()).unwrap();
+    let history = vfs.history(&file_id).unwrap();
+    let versions: Vec<_> = history.revisions.iter().map(|revision| revision.version).collect();
+    assert_eq!(versions, [1, 2, 7]);
+    assert!(matches!(vfs.redo(&file_id), Err(Error::InvalidArgument(_))));
+}
+
+#[test]
+fn revert_restores_a_revision_as_a_new_one() {
+    let mut vfs = VirtualFileSystem::new();
+    let file_id = vfs.create_file(Path::new("/a.txt"), "good".to_string()).unwrap();
+    vfs.write_file(&file_id, "trashed".to_string()).unwrap();
+
+    assert_eq!(vfs.revision(&file_id, 1).unwrap().content, "good");
+    assert_eq!(vfs.revert(&file_id, 1).unwrap(), 3);
+    assert_eq!(content(&vfs, &file_id), "good");
+    assert_eq!(vfs.history(&file_id).unwrap().revisions.len(), 3);
+
+    // The revert itself can be undone
+    vfs.undo(&file_id).unwrap();
+    assert_eq!(content(&vfs, &file_id), "trashed");
+    assert!(matches!(vfs.revert(&file_id, 42), Err(Error::NotFound(_))));
+}
+
+#[test]
+fn history_is_bounded_by_the_configured_limits() {
+    let config = AppConfig {
+        history: HistoryConfig { max_revisions: 3, max_bytes: 1024 },
+        ..Default::default()
+    };
+    let state = AppState::with_config(VirtualFileSystem::new(), config);
+    let mut vfs = state.vfs.try_write().unwrap();
+
+    let file_id = vfs.create_file(Path::new("/a.txt"), String::new()).unwrap();
+    for n in 1..=5 {
+        vfs.write_file(&file_id, n.to_string()).unwrap();
+    }
+    let versions: Vec<_> = vfs.history(&file_id).unwrap().revisions.iter().map(|revision| revision.version).collect();
+    assert_eq!(versions, [4, 5, 6]);
+
+    // The current revision is kept even when it alone is over the limit
+    vfs.set_history_limits(HistoryConfig { max_revisions: 3, max_bytes: 4 });
+    vfs.write_file(&file_id, "too large".to_string()).unwrap();
+    let history = vfs.history(&file_id).unwrap();
+    assert_eq!(history.revisions.len(), 1);
+    assert!(!history.can_undo);
+}
+
+#[tokio::test]
+async fn mcp_records_the_session_and_diffs_against_revisions() {
+    let state = Arc::new(AppState::new(VirtualFileSystem::new()));
+    state.vfs.write().await.create_file(Path::new("/a.js"), "let x = 1;\n".to_string()).unwrap();
+    let author = Author::agent(Some("session-7"));
+    let run = |request: Value| {
+        let state = state.clone();
+        let author = author.clone();
+        async move {
+            let request: McpRequest = serde_json::from_value(request).unwrap();
+            match handle_mcp_request_as(request, &author, &state).await {
+                McpResponse::Success { data } => data,
+                other => panic!("unexpected response: {:?}", other),
+            }
+        }
+    };
+
+    run(json!({ "type": "file.update", "id": "/a.js", "content": "let x = 2;\n" })).await;
+    let history = run(json!({ "type": "file.history", "path": "/a.js" })).await;
+    assert_eq!(history["revisions"][1]["author"], json!({ "kind": "agent", "session_id": "session-7" }));
+    assert_eq!(history["revisions"][0]["author"]["kind"], "user");
+
+    let diff = run(json!({
+        "type": "file.diff", "from": { "file": "/a.js", "version": 1 }, "to": { "file": "/a.js" }
+    })).await;
+    assert_eq!((diff["added"].clone(), diff["removed"].clone()), (json!(1), json!(1)));
+
+    let undone = run(json!({ "type": "file.undo", "id": "/a.js" })).await;
+    assert_eq!(undone["version"], 3);
+    let revision = run(json!({ "type": "file.revision", "id": "/a.js", "version": 2 })).await;
+    assert_eq!(revision["content"], "let x = 2;\n");
+    run(json!({ "type": "file.redo", "id": "/a.js" })).await;
+    run(json!({ "type": "file.revert", "id": "/a.js", "version": 1 })).await;
+    assert_eq!(state.vfs.read().await.file_by_path(Path::new("/a.js")).unwrap().content, "let x = 1;\n");
+}
+
+#[tokio::test]
+async fn rest_lists_reads_and_moves_through_history() {
+    let state = AppState::new(VirtualFileSystem::new());
+    state.vfs.write().await.create_file(Path::new("/a.txt"), "one".to_string()).unwrap();
+    let send = |request: Request<Body>| {
+        let app = api::router(state.clone());
+        async move {
+            let response = app.oneshot(request).await.unwrap();
+            let status = response.status();
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, serde_json::from_slice::<Value>(&bytes).unwrap_or(Value::Null))
+        }
+    };
+    let post = |uri: &str, body: Value| {
+        Request::post(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    send(post("/api/files/a.txt", json!({ "content": "two" }))).await;
+    let (status, history) = send(Request::get("/api/history/a.txt").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(history["revisions"][1]["author"]["kind"], "api");
+
+    let (_, revision) = send(Request::get("/api/history/a.txt?version=1").body(Body::empty()).unwrap()).await;
+    assert_eq!(revision["content"], "one");
+
+    let (status, undone) = send(post("/api/history/a.txt", json!({ "action": "undo" }))).await;
+    assert_eq!((status, undone["version"].clone()), (StatusCode::OK, json!(3)));
+    let (status, _) = send(post("/api/history/a.txt", json!({ "action": "revert", "version": 9 }))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let vfs = state.vfs.read().await;
+    assert_eq!(vfs.file_by_path(Path::new("/a.txt")).unwrap().content, "one");
+    assert_eq!(vfs.history(vfs.file_id_at(Path::new("/a.txt")).unwrap()).unwrap().revisions[0].author.kind, AuthorKind::User);
+}
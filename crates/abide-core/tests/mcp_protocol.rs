@@ -0,0 +1,303 @@
+//! JSON-RPC 2.0 / MCP lifecycle behaviour of `mcp::handle_message`.
+
+use std::sync::Arc;
+
+use abide_core::mcp::{self, LATEST_PROTOCOL_VERSION};
+use abide_core::{AppState, VirtualFileSystem};
+use serde_json::{json, Value};
+
+struct Client {
+    state: Arc<AppState>,
+    session_id: String,
+}
+
+impl Client {
+    fn new() -> Self {
+        let state = Arc::new(AppState::new(VirtualFileSystem::new()));
+        let session_id = state.create_session();
+        Self { state, session_id }
+    }
+
+    async fn send(&self, message: Value) -> Option<Value> {
+        mcp::handle_message(&message.to_string(), &self.session_id, &self.state)
+            .await
+            .map(|text| serde_json::from_str(&text).unwrap())
+    }
+
+    async fn initialize(&self) -> Value {
+        let response = self.send(json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": LATEST_PROTOCOL_VERSION,
+                "capabilities": {},
+                "clientInfo": { "name": "test", "version": "1.0" }
+            }
+        })).await.unwrap();
+        assert!(self.send(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })).await.is_none());
+        response
+    }
+
+    async fn call_tool(&self, id: i64, name: &str, arguments: Value) -> Value {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments }
+        })).await.unwrap()
+    }
+}
+
+#[tokio::test]
+async fn initialize_echoes_supported_version() {
+    let client = Client::new();
+    let response = client.initialize().await;
+
+    assert_eq!(response["jsonrpc"], "2.0");
+    assert_eq!(response["id"], 0);
+    assert_eq!(response["result"]["protocolVersion"], LATEST_PROTOCOL_VERSION);
+    assert_eq!(response["result"]["serverInfo"]["name"], "abide");
+    assert!(response["result"]["capabilities"]["tools"].is_object());
+
+    let session = client.state.sessions.get(&client.session_id).unwrap();
+    assert!(session.initialized);
+    assert_eq!(session.client_info.as_ref().unwrap()["name"], "test");
+}
+
+#[tokio::test]
+async fn initialize_offers_latest_for_unknown_version() {
+    let client = Client::new();
+    let response = client.send(json!({
+        "jsonrpc": "2.0",
+        "id": "init",
+        "method": "initialize",
+        "params": { "protocolVersion": "1999-01-01", "capabilities": {} }
+    })).await.unwrap();
+
+    assert_eq!(response["id"], "init");
+    assert_eq!(response["result"]["protocolVersion"], LATEST_PROTOCOL_VERSION);
+}
+
+#[tokio::test]
+async fn requests_before_initialize_are_rejected() {
+    let client = Client::new();
+    let response = client.send(json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" })).await.unwrap();
+    assert_eq!(response["error"]["code"], -32002);
+
+    let ping = client.send(json!({ "jsonrpc": "2.0", "id": 2, "method": "ping" })).await.unwrap();
+    assert_eq!(ping["result"], json!({}));
+}
+
+#[tokio::test]
+async fn tools_list_includes_file_tools() {
+    let client = Client::new();
+    client.initialize().await;
+
+    let response = client.send(json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" })).await.unwrap();
+    let tools = response["result"]["tools"].as_array().unwrap();
+    let create = tools.iter().find(|tool| tool["name"] == "file_create").unwrap();
+    assert_eq!(create["inputSchema"]["type"], "object");
+}
+
+#[tokio::test]
+async fn tools_call_runs_vfs_operations() {
+    let client = Client::new();
+    client.initialize().await;
+
+    let created = client.call_tool(7, "file_create", json!({ "path": "/a.js", "content": "let a;" })).await;
+    assert_eq!(created["id"], 7);
+    assert_eq!(created["result"]["isError"], false);
+    let file_id = created["result"]["structuredContent"]["id"].as_str().unwrap().to_string();
+
+    let read = client.call_tool(8, "file_read", json!({ "id": file_id })).await;
+    assert_eq!(read["result"]["structuredContent"]["content"], "let a;");
+
+    let missing = client.call_tool(9, "file_read", json!({ "id": "nope" })).await;
+    assert_eq!(missing["result"]["isError"], true);
+}
+
+#[tokio::test]
+async fn tools_call_rejects_unknown_tool_and_bad_arguments() {
+    let client = Client::new();
+    client.initialize().await;
+
+    let unknown = client.call_tool(1, "rm_rf", json!({})).await;
+    assert_eq!(unknown["error"]["code"], -32602);
+
+    let invalid = client.call_tool(2, "file_create", json!({ "path": 5 })).await;
+    assert_eq!(invalid["error"]["code"], -32602);
+}
+
+#[tokio::test]
+async fn malformed_messages_get_standard_errors() {
+    let client = Client::new();
+
+    let text = mcp::handle_message("{not json", &client.session_id, &client.state).await.unwrap();
+    let parse_error: Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(parse_error["error"]["code"], -32700);
+    assert!(parse_error["id"].is_null());
+
+    let invalid = client.send(json!({ "jsonrpc": "2.0", "id": 3 })).await.unwrap();
+    assert_eq!(invalid["error"]["code"], -32600);
+    assert_eq!(invalid["id"], 3);
+
+    let unknown = client.send(json!({ "jsonrpc": "2.0", "id": 4, "method": "bogus" })).await.unwrap();
+    assert_eq!(unknown["error"]["code"], -32601);
+}
+
+#[tokio::test]
+async fn batches_are_answered_in_one_array() {
+    let client = Client::new();
+    client.initialize().await;
+
+    let response = client.send(json!([
+        { "jsonrpc": "2.0", "id": 1, "method": "ping" },
+        { "jsonrpc": "2.0", "method": "notifications/initialized" },
+        { "jsonrpc": "2.0", "id": 2, "method": "tools/list" }
+    ])).await.unwrap();
+
+    let responses = response.as_array().unwrap();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[1]["id"], 2);
+}
+
+#[tokio::test]
+async fn vfs_files_are_listed_and_read_as_resources() {
+    let client = Client::new();
+    client.initialize().await;
+    client.call_tool(1, "directory_create", json!({ "path": "/src" })).await;
+    client.call_tool(2, "file_create", json!({ "path": "/src/main.rs", "content": "fn main() {}" })).await;
+
+    let listed = client.send(json!({ "jsonrpc": "2.0", "id": 3, "method": "resources/list" })).await.unwrap();
+    let resources = listed["result"]["resources"].as_array().unwrap();
+    assert_eq!(resources.len(), 1);
+    assert_eq!(resources[0]["uri"], "abide://vfs/src/main.rs");
+    assert_eq!(resources[0]["name"], "main.rs");
+    assert_eq!(resources[0]["mimeType"], "text/x-rust");
+
+    let read = client.send(json!({
+        "jsonrpc": "2.0", "id": 4, "method": "resources/read",
+        "params": { "uri": "abide://vfs/src/main.rs" }
+    })).await.unwrap();
+    assert_eq!(read["result"]["contents"][0]["text"], "fn main() {}");
+
+    let missing = client.send(json!({
+        "jsonrpc": "2.0", "id": 5, "method": "resources/read",
+        "params": { "uri": "abide://vfs/nope.rs" }
+    })).await.unwrap();
+    assert_eq!(missing["error"]["code"], -32002);
+    assert_eq!(missing["error"]["data"]["uri"], "abide://vfs/nope.rs");
+}
+
+#[tokio::test]
+async fn subscribed_sessions_are_notified_of_writes_and_deletes() {
+    let client = Client::new();
+    client.initialize().await;
+    let created = client.call_tool(1, "file_create", json!({ "path": "/a.js", "content": "1" })).await;
+    let file_id = created["result"]["structuredContent"]["id"].as_str().unwrap().to_string();
+
+    let mut outbound = client.state.subscribe_session(&client.session_id).unwrap();
+    let subscribed = client.send(json!({
+        "jsonrpc": "2.0", "id": 2, "method": "resources/subscribe",
+        "params": { "uri": "abide://vfs/a.js" }
+    })).await.unwrap();
+    assert_eq!(subscribed["result"], json!({}));
+
+    client.call_tool(3, "file_update", json!({ "id": file_id, "content": "2" })).await;
+    let notification: Value = serde_json::from_str(&outbound.try_recv().unwrap()).unwrap();
+    assert_eq!(notification["method"], "notifications/resources/updated");
+    assert_eq!(notification["params"]["uri"], "abide://vfs/a.js");
+
+    client.call_tool(4, "file_delete", json!({ "id": file_id })).await;
+    assert!(outbound.try_recv().is_ok());
+
+    client.send(json!({
+        "jsonrpc": "2.0", "id": 5, "method": "resources/unsubscribe",
+        "params": { "uri": "abide://vfs/a.js" }
+    })).await.unwrap();
+    let session = client.state.sessions.get(&client.session_id).unwrap();
+    assert!(session.resource_subscriptions.is_empty());
+}
+
+#[tokio::test]
+async fn builtin_prompts_are_filled_from_the_vfs() {
+    let client = Client::new();
+    client.initialize().await;
+    client.call_tool(1, "file_create", json!({ "path": "/lib.rs", "content": "pub fn add() {}\npub fn sub() {}" })).await;
+
+    let listed = client.send(json!({ "jsonrpc": "2.0", "id": 2, "method": "prompts/list" })).await.unwrap();
+    let names: Vec<_> = listed["result"]["prompts"].as_array().unwrap()
+        .iter()
+        .map(|prompt| prompt["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["explain_file", "refactor_selection", "write_tests"]);
+    assert!(listed["result"]["prompts"][0].get("template").is_none());
+
+    let explained = client.send(json!({
+        "jsonrpc": "2.0", "id": 3, "method": "prompts/get",
+        "params": { "name": "explain_file", "arguments": { "path": "/lib.rs" } }
+    })).await.unwrap();
+    let text = explained["result"]["messages"][0]["content"]["text"].as_str().unwrap();
+    assert!(text.contains("`/lib.rs`"));
+    assert!(text.contains("```rust\npub fn add() {}"));
+
+    let missing = client.send(json!({
+        "jsonrpc": "2.0", "id": 4, "method": "prompts/get",
+        "params": { "name": "explain_file", "arguments": {} }
+    })).await.unwrap();
+    assert_eq!(missing["error"]["code"], -32602);
+}
+
+#[tokio::test]
+async fn refactor_prompt_uses_editor_selection() {
+    let client = Client::new();
+    client.initialize().await;
+    let created = client.call_tool(1, "file_create", json!({ "path": "/lib.rs", "content": "pub fn add() {}\npub fn sub() {}" })).await;
+    let file_id = created["result"]["structuredContent"]["id"].as_str().unwrap().to_string();
+
+    let get = json!({
+        "jsonrpc": "2.0", "id": 2, "method": "prompts/get",
+        "params": { "name": "refactor_selection", "arguments": { "path": "/lib.rs", "instructions": "Rename it." } }
+    });
+    let unselected = client.send(get.clone()).await.unwrap();
+    assert_eq!(unselected["error"]["code"], -32602);
+
+    *client.state.selection.write().await = Some(serde_json::from_value(json!({
+        "file_id": file_id,
+        "start": { "line": 1, "column": 0 },
+        "end": { "line": 1, "column": 10 }
+    })).unwrap());
+
+    let filled = client.send(get).await.unwrap();
+    let text = filled["result"]["messages"][0]["content"]["text"].as_str().unwrap();
+    assert!(text.contains("Goal: Rename it."));
+    assert!(text.contains("```rust\npub fn sub\n```"));
+}
+
+#[tokio::test]
+async fn custom_prompts_are_loaded_from_config_dir() {
+    let client = Client::new();
+    client.initialize().await;
+    client.call_tool(1, "file_create", json!({ "path": "/a.js", "content": "let a = '{{path}}';" })).await;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("review.json"), json!({
+        "name": "review",
+        "arguments": [{ "name": "path", "required": true }, { "name": "focus" }],
+        "template": "Review {{path}}{{#focus}} for {{focus}}{{/focus}}: {{content}}"
+    }).to_string()).unwrap();
+    std::fs::write(dir.path().join("broken.json"), "{").unwrap();
+    client.state.config.write().await.mcp.prompts_dir = Some(dir.path().to_path_buf());
+
+    let listed = client.send(json!({ "jsonrpc": "2.0", "id": 2, "method": "prompts/list" })).await.unwrap();
+    assert_eq!(listed["result"]["prompts"].as_array().unwrap().len(), 4);
+
+    let review = client.send(json!({
+        "jsonrpc": "2.0", "id": 3, "method": "prompts/get",
+        "params": { "name": "review", "arguments": { "path": "/a.js" } }
+    })).await.unwrap();
+    // File content is inserted verbatim, not expanded again.
+    assert_eq!(review["result"]["messages"][0]["content"]["text"], "Review /a.js: let a = '{{path}}';");
+}
@@ -0,0 +1,210 @@
+//! Applying unified diffs with `file.patch`.
+
+use std::path::Path;
+
+use abide_core::api;
+use abide_core::state::patch::{HunkOutcome, PatchAction, DEFAULT_FUZZ};
+use abide_core::{AppState, Error, VirtualFileSystem};
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+const MAIN: &str = "use std::io;\n\nfn main() {\n    let x = 1;\n    println!(\"{}\", x);\n}\n";
+
+fn project() -> VirtualFileSystem {
+    let mut vfs = VirtualFileSystem::new();
+    vfs.create_file_all(Path::new("/src/main.rs"), MAIN.to_string()).unwrap();
+    vfs.create_file_all(Path::new("/src/old.rs"), "pub fn old() {}\n".to_string()).unwrap();
+    vfs
+}
+
+fn content(vfs: &VirtualFileSystem, path: &str) -> String {
+    vfs.file_by_path(Path::new(path)).unwrap().content.to_string()
+}
+
+#[test]
+fn multi_file_patch_creates_modifies_and_deletes() {
+    let mut vfs = project();
+    let patch = "\
+diff --git a/src/main.rs b/src/main.rs
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -3,4 +3,5 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
++    let y = x * 2;
+     println!(\"{}\", x);
+ }
+--- /dev/null
++++ b/src/lib.rs
+@@ -0,0 +1,2 @@
++pub mod util;
++pub fn lib() {}
+--- a/src/old.rs
++++ /dev/null
+@@ -1 +0,0 @@
+-pub fn old() {}
+";
+
+    let report = vfs.apply_patch(patch, DEFAULT_FUZZ).unwrap();
+    let actions: Vec<_> = report.files.iter().map(|file| file.action).collect();
+    assert_eq!(actions, [PatchAction::Modify, PatchAction::Create, PatchAction::Delete]);
+
+    assert_eq!(
+        content(&vfs, "/src/main.rs"),
+        "use std::io;\n\nfn main() {\n    let x = 2;\n    let y = x * 2;\n    println!(\"{}\", x);\n}\n"
+    );
+    assert_eq!(content(&vfs, "/src/lib.rs"), "pub mod util;\npub fn lib() {}\n");
+    assert!(vfs.resolve_file("/src/old.rs").is_err());
+}
+
+#[test]
+fn hunks_apply_at_an_offset_and_with_fuzz() {
+    let mut vfs = project();
+    // Wrong line numbers, an edited context line, and a blank context line
+    // without its leading space
+    let patch = "\
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -10,6 +10,6 @@
+ use std::io;
+
+ fn main() {
+-    let x = 1;
++    let x = 3;
+     println!(\"{}\", x);
+ }   // changed
+";
+
+    let report = vfs.apply_patch(patch, DEFAULT_FUZZ).unwrap();
+    match &report.files[0].hunks[0].outcome {
+        HunkOutcome::Applied { line, offset, fuzz } => {
+            assert_eq!((*line, *offset, *fuzz), (1, -9, 1));
+        }
+        other => panic!("unexpected outcome: {:?}", other),
+    }
+    assert!(content(&vfs, "/src/main.rs").contains("let x = 3;"));
+}
+
+#[test]
+fn rejected_hunks_leave_every_file_untouched() {
+    let mut vfs = project();
+    let patch = "\
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1 @@
++fn new() {}
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -4,1 +4,1 @@
+-    let x = 1;
++    let x = 5;
+@@ -5,1 +5,1 @@
+-    let z = 9;
++    let z = 10;
+";
+
+    let Err(Error::PatchRejected(report)) = vfs.apply_patch(patch, DEFAULT_FUZZ) else {
+        panic!("patch should be rejected");
+    };
+    let hunks = &report.files[1].hunks;
+    assert!(matches!(hunks[0].outcome, HunkOutcome::Applied { .. }));
+    match &hunks[1].outcome {
+        HunkOutcome::Rejected { expected, .. } => assert_eq!(expected, "    let z = 9;"),
+        other => panic!("unexpected outcome: {:?}", other),
+    }
+
+    assert_eq!(content(&vfs, "/src/main.rs"), MAIN);
+    assert!(vfs.resolve_file("/src/new.rs").is_err());
+}
+
+#[test]
+fn file_level_problems_are_reported() {
+    let mut vfs = project();
+    let patch = "\
+--- a/src/missing.rs
++++ b/src/missing.rs
+@@ -1 +1 @@
+-a
++b
+--- /dev/null
++++ b/src/old.rs
+@@ -0,0 +1 @@
++again
+";
+
+    let Err(Error::PatchRejected(report)) = vfs.apply_patch(patch, DEFAULT_FUZZ) else {
+        panic!("patch should be rejected");
+    };
+    assert_eq!(report.files[0].error.as_deref(), Some("file does not exist"));
+    assert_eq!(report.files[1].error.as_deref(), Some("file already exists"));
+    assert!(matches!(vfs.apply_patch("not a diff", DEFAULT_FUZZ), Err(Error::InvalidArgument(_))));
+}
+
+#[test]
+fn renames_and_missing_trailing_newlines() {
+    let mut vfs = project();
+    let old_id = vfs.resolve_file("/src/old.rs").unwrap();
+    let patch = "\
+--- a/src/old.rs
++++ b/src/renamed.rs
+@@ @@
+-pub fn old() {}
++pub fn renamed() {}
+\\ No newline at end of file
+";
+
+    let report = vfs.apply_patch(patch, DEFAULT_FUZZ).unwrap();
+    assert_eq!(report.files[0].renamed_from.as_deref(), Some(Path::new("/src/old.rs")));
+    assert_eq!(content(&vfs, "/src/renamed.rs"), "pub fn renamed() {}");
+    assert!(vfs.resolve_file("/src/old.rs").is_err());
+
+    // The renamed file is the same file, one version on
+    let renamed = vfs.file_by_path(Path::new("/src/renamed.rs")).unwrap();
+    assert_eq!(renamed.id, old_id);
+    assert_eq!(renamed.version, 2);
+    assert_eq!(vfs.history(&old_id).unwrap().revisions.len(), 2);
+
+    // Renames into a new directory create it
+    let patch = "\
+--- a/src/renamed.rs
++++ b/lib/renamed.rs
+@@ @@
+-pub fn renamed() {}
+\\ No newline at end of file
++pub fn moved() {}
+";
+    vfs.apply_patch(patch, DEFAULT_FUZZ).unwrap();
+    assert_eq!(vfs.resolve_file("/lib/renamed.rs").unwrap(), old_id);
+    assert_eq!(content(&vfs, "/lib/renamed.rs"), "pub fn moved() {}\n");
+}
+
+#[tokio::test]
+async fn rest_patch_reports_rejections() {
+    let state = AppState::new(project());
+    let post = |patch: &str| {
+        Request::post("/api/patch")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(json!({ "patch": patch }).to_string()))
+            .unwrap()
+    };
+
+    let rejected = api::router(state.clone())
+        .oneshot(post("--- a/src/old.rs\n+++ b/src/old.rs\n@@ -1 +1 @@\n-nope\n+yes\n"))
+        .await
+        .unwrap();
+    assert_eq!(rejected.status(), StatusCode::CONFLICT);
+    let bytes = axum::body::to_bytes(rejected.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["kind"], "patch_rejected");
+    assert_eq!(body["report"]["files"][0]["hunks"][0]["status"], "rejected");
+
+    let applied = api::router(state.clone())
+        .oneshot(post("--- a/src/old.rs\n+++ b/src/old.rs\n@@ -1 +1 @@\n-pub fn old() {}\n+pub fn new() {}\n"))
+        .await
+        .unwrap();
+    assert_eq!(applied.status(), StatusCode::OK);
+    assert_eq!(content(&*state.vfs.read().await, "/src/old.rs"), "pub fn new() {}\n");
+}
@@ -0,0 +1,67 @@
+//! Settings updates, applied the same way over MCP and REST.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use abide_core::api;
+use abide_core::mcp::{handle_mcp_request, McpRequest, McpResponse};
+use abide_core::{AppState, VirtualFileSystem};
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn mcp_settings_update_changes_the_config() {
+    let state = Arc::new(AppState::new(VirtualFileSystem::new()));
+    let file_id = state.vfs.write().await.create_file(Path::new("/a.txt"), "1".to_string()).unwrap();
+
+    let request: McpRequest = serde_json::from_value(json!({
+        "type": "settings.update",
+        "settings": { "editor": { "tab_size": 2 }, "history": { "max_revisions": 2 }, "unknown": true }
+    })).unwrap();
+    match handle_mcp_request(request, &state).await {
+        McpResponse::Success { data } => assert_eq!(data["settings"]["editor"]["tab_size"], 2),
+        other => panic!("unexpected response: {:?}", other),
+    }
+    assert_eq!(state.config.read().await.editor.tab_size, 2);
+
+    // New history limits reach the VFS
+    let mut vfs = state.vfs.write().await;
+    for content in ["2", "3", "4"] {
+        vfs.write_file(&file_id, content.to_string()).unwrap();
+    }
+    assert_eq!(vfs.history(&file_id).unwrap().revisions.len(), 2);
+    drop(vfs);
+
+    let request: McpRequest = serde_json::from_value(json!({ "type": "settings.update", "settings": [] })).unwrap();
+    match handle_mcp_request(request, &state).await {
+        McpResponse::Error { data, .. } => assert_eq!(data["kind"], "invalid_argument"),
+        other => panic!("unexpected response: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn rest_settings_update_returns_the_new_settings() {
+    let state = AppState::new(VirtualFileSystem::new());
+    let update = |settings: Value| {
+        Request::post("/api/settings")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(settings.to_string()))
+            .unwrap()
+    };
+
+    let response = api::router(state.clone())
+        .oneshot(update(json!({ "theme": { "mode": "light" }, "editor": { "auto_save": false } })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["theme"]["mode"], "light");
+    assert_eq!(body["editor"]["auto_save"], false);
+    assert!(!state.config.read().await.editor.auto_save);
+
+    let rejected = api::router(state.clone()).oneshot(update(json!("dark"))).await.unwrap();
+    assert_eq!(rejected.status(), StatusCode::BAD_REQUEST);
+}
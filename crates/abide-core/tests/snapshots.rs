@@ -0,0 +1,183 @@
+//! Named whole-workspace snapshots.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use abide_core::api;
+use abide_core::mcp::{handle_mcp_request, McpRequest, McpResponse};
+use abide_core::state::diff::{DiffOptions, DiffSide};
+use abide_core::state::patch::DEFAULT_FUZZ;
+use abide_core::{AppState, Error, VirtualFileSystem};
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+fn project() -> VirtualFileSystem {
+    let mut vfs = VirtualFileSystem::new();
+    vfs.create_file_all(Path::new("/src/main.rs"), "fn main() {}\n".to_string()).unwrap();
+    vfs.create_file_all(Path::new("/src/util.rs"), "pub fn util() {}\n".to_string()).unwrap();
+    vfs
+}
+
+/// What an agent might do after checkpointing: edit, create, delete, move.
+fn trash(vfs: &mut VirtualFileSystem) {
+    let main = vfs.resolve_file("/src/main.rs").unwrap();
+    vfs.write_file(&main, "fn main() { broken }\n".to_string()).unwrap();
+    vfs.create_file_all(Path::new("/tmp/scratch.rs"), "// scratch\n".to_string()).unwrap();
+    vfs.delete_file(&vfs.resolve_file("/src/util.rs").unwrap()).unwrap();
+    vfs.move_node(Path::new("/src/main.rs"), Path::new("/main.rs")).unwrap();
+}
+
+fn content(vfs: &VirtualFileSystem, path: &str) -> String {
+    vfs.file_by_path(Path::new(path)).unwrap().content.to_string()
+}
+
+#[test]
+fn restore_puts_back_the_tree_and_contents() {
+    let mut vfs = project();
+    let main = vfs.resolve_file("/src/main.rs").unwrap();
+    let info = vfs.create_snapshot("before").unwrap();
+    assert_eq!(info.files, 2);
+    trash(&mut vfs);
+
+    let report = vfs.restore_snapshot("before").unwrap();
+    assert_eq!(report.modified, [PathBuf::from("/src/main.rs")]);
+    assert_eq!(report.recreated, [PathBuf::from("/src/util.rs")]);
+    assert_eq!(report.removed, [PathBuf::from("/tmp/scratch.rs")]);
+
+    assert_eq!(content(&vfs, "/src/main.rs"), "fn main() {}\n");
+    assert_eq!(content(&vfs, "/src/util.rs"), "pub fn util() {}\n");
+    assert!(vfs.list_directory(Path::new("/tmp")).is_err());
+    assert!(vfs.resolve_file("/main.rs").is_err());
+
+    // Same file, newer version, and the restore can be undone
+    assert_eq!(vfs.resolve_file("/src/main.rs").unwrap(), main);
+    assert_eq!(vfs.read_file(&main).unwrap().version, 3);
+    vfs.undo(&main).unwrap();
+    assert_eq!(content(&vfs, "/src/main.rs"), "fn main() { broken }\n");
+}
+
+#[test]
+fn restoring_a_moved_file_notifies_both_paths() {
+    let mut vfs = project();
+    vfs.create_snapshot("before").unwrap();
+    vfs.create_directory(Path::new("/lib")).unwrap();
+    vfs.move_node(Path::new("/src/util.rs"), Path::new("/lib/util.rs")).unwrap();
+
+    let notified = Arc::new(Mutex::new(Vec::new()));
+    let recorder = notified.clone();
+    vfs.set_change_listener(Arc::new(move |path: &Path| recorder.lock().unwrap().push(path.to_path_buf())));
+    vfs.restore_snapshot("before").unwrap();
+
+    // Unchanged content, but subscribers to either path need to know
+    let mut notified = notified.lock().unwrap().clone();
+    notified.sort();
+    assert_eq!(notified, [PathBuf::from("/lib/util.rs"), PathBuf::from("/src/util.rs")]);
+}
+
+#[test]
+fn snapshots_are_listed_diffed_and_deleted() {
+    let mut vfs = project();
+    vfs.create_snapshot("one").unwrap();
+    assert!(matches!(vfs.create_snapshot("one"), Err(Error::Conflict(_))));
+    trash(&mut vfs);
+    vfs.create_snapshot("two").unwrap();
+
+    let names: Vec<_> = vfs.snapshots().into_iter().map(|info| info.name).collect();
+    assert_eq!(names, ["one", "two"]);
+
+    let diff = vfs.diff_snapshot("one", &DiffOptions::default()).unwrap();
+    let paths: Vec<_> = diff.files.iter().map(|file| (file.old_path.as_str(), file.new_path.as_str())).collect();
+    assert_eq!(paths, [
+        ("/dev/null", "/main.rs"),
+        ("/src/main.rs", "/dev/null"),
+        ("/src/util.rs", "/dev/null"),
+        ("/dev/null", "/tmp/scratch.rs"),
+    ]);
+    assert!(vfs.diff_snapshot("two", &DiffOptions::default()).unwrap().files.is_empty());
+
+    // The diff applies as a patch to the snapshotted state
+    let mut replayed = project();
+    replayed.apply_patch(&diff.unified, DEFAULT_FUZZ).unwrap();
+    assert_eq!(content(&replayed, "/main.rs"), "fn main() { broken }\n");
+    assert_eq!(content(&replayed, "/tmp/scratch.rs"), "// scratch\n");
+    assert!(replayed.resolve_file("/src/util.rs").is_err());
+
+    // A single file can be compared with its snapshot copy
+    let side = |snapshot: Option<&str>, file: &str| DiffSide {
+        file: file.to_string(),
+        version: None,
+        snapshot: snapshot.map(str::to_string),
+    };
+    let file_diff = vfs.diff(&side(Some("one"), "/src/main.rs"), &side(None, "/main.rs"), &DiffOptions::default()).unwrap();
+    assert_eq!((file_diff.added, file_diff.removed), (1, 1));
+
+    vfs.delete_snapshot("one").unwrap();
+    assert!(matches!(vfs.restore_snapshot("one"), Err(Error::NotFound(_))));
+}
+
+#[tokio::test]
+async fn agents_checkpoint_over_mcp() {
+    let state = Arc::new(AppState::new(project()));
+    let run = |request: Value| {
+        let state = state.clone();
+        async move {
+            let request: McpRequest = serde_json::from_value(request).unwrap();
+            handle_mcp_request(request, &state).await
+        }
+    };
+
+    assert!(matches!(run(json!({ "type": "snapshot.create", "name": "checkpoint" })).await, McpResponse::Success { .. }));
+    run(json!({ "type": "file.update", "id": "/src/main.rs", "content": "oops\n" })).await;
+
+    match run(json!({ "type": "snapshot.diff", "name": "checkpoint", "whitespace": "ignore_all" })).await {
+        McpResponse::Success { data } => assert_eq!(data["files"][0]["old_path"], "/src/main.rs"),
+        other => panic!("unexpected response: {:?}", other),
+    }
+    match run(json!({ "type": "snapshot.restore", "name": "checkpoint" })).await {
+        McpResponse::Success { data } => assert_eq!(data["modified"], json!(["/src/main.rs"])),
+        other => panic!("unexpected response: {:?}", other),
+    }
+    match run(json!({ "type": "snapshot.list" })).await {
+        McpResponse::Success { data } => assert_eq!(data["snapshots"][0]["name"], "checkpoint"),
+        other => panic!("unexpected response: {:?}", other),
+    }
+    run(json!({ "type": "snapshot.delete", "name": "checkpoint" })).await;
+    match run(json!({ "type": "snapshot.restore", "name": "checkpoint" })).await {
+        McpResponse::Error { code, .. } => assert_eq!(code, -32001),
+        other => panic!("unexpected response: {:?}", other),
+    }
+    assert_eq!(content(&*state.vfs.read().await, "/src/main.rs"), "fn main() {}\n");
+}
+
+#[tokio::test]
+async fn rest_snapshot_routes() {
+    let state = AppState::new(project());
+    let send = |request: Request<Body>| api::router(state.clone()).oneshot(request);
+
+    let created = send(
+        Request::post("/api/snapshots")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(json!({ "name": "base" }).to_string()))
+            .unwrap(),
+    ).await.unwrap();
+    assert_eq!(created.status(), StatusCode::CREATED);
+
+    let main = state.vfs.read().await.resolve_file("/src/main.rs").unwrap();
+    state.vfs.write().await.write_file(&main, "changed\n".to_string()).unwrap();
+
+    let diff = send(Request::get("/api/snapshots/base/diff?context=0").body(Body::empty()).unwrap()).await.unwrap();
+    let bytes = axum::body::to_bytes(diff.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["files"][0]["hunks"][0]["lines"].as_array().unwrap().len(), 2);
+
+    let restored = send(Request::post("/api/snapshots/base/restore").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(restored.status(), StatusCode::OK);
+    let deleted = send(Request::delete("/api/snapshots/base").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(deleted.status(), StatusCode::NO_CONTENT);
+    let missing = send(Request::delete("/api/snapshots/base").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+    assert_eq!(content(&*state.vfs.read().await, "/src/main.rs"), "fn main() {}\n");
+}
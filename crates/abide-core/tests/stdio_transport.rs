@@ -0,0 +1,50 @@
+//! Line-delimited MCP over an in-memory stdio pair.
+
+use std::sync::Arc;
+
+use abide_core::mcp::{self, LATEST_PROTOCOL_VERSION};
+use abide_core::{AppState, VirtualFileSystem};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+#[tokio::test]
+async fn stdio_session_round_trip() {
+    let state = Arc::new(AppState::new(VirtualFileSystem::new()));
+    let (client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+    let server = tokio::spawn(mcp::stdio::serve(server_in, server_out, state.clone()));
+
+    let mut writer = client_in;
+    let mut reader = BufReader::new(client_out).lines();
+    let messages = [
+        json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": { "protocolVersion": LATEST_PROTOCOL_VERSION, "capabilities": {} }
+        }),
+        json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        json!({
+            "jsonrpc": "2.0", "id": 2, "method": "tools/call",
+            "params": { "name": "file_create", "arguments": { "path": "/stdio.txt", "content": "hi" } }
+        }),
+    ];
+    for message in &messages {
+        writer.write_all(format!("{}\n", message).as_bytes()).await.unwrap();
+    }
+
+    let initialize: Value = serde_json::from_str(&reader.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(initialize["id"], 1);
+    assert_eq!(initialize["result"]["protocolVersion"], LATEST_PROTOCOL_VERSION);
+
+    // The notification produces no line, so the next one answers id 2.
+    let created: Value = serde_json::from_str(&reader.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(created["id"], 2);
+    assert_eq!(created["result"]["isError"], false);
+    assert_eq!(state.sessions.len(), 1);
+
+    drop(writer);
+    server.await.unwrap().unwrap();
+    assert!(state.sessions.is_empty());
+
+    let nodes = state.vfs.read().await.list_directory(std::path::Path::new("/")).unwrap();
+    assert_eq!(nodes[0].name, "stdio.txt");
+}
@@ -0,0 +1,228 @@
+//! MCP Streamable HTTP transport on `/mcp`.
+
+use abide_core::api::{self, streamable_http::{self, SESSION_HEADER}};
+use abide_core::mcp::LATEST_PROTOCOL_VERSION;
+use abide_core::{AppState, VirtualFileSystem};
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::response::Response;
+use futures::StreamExt;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tower::ServiceExt;
+
+fn post(session_id: Option<&str>, accept: &str, message: Value) -> Request<Body> {
+    let mut request = Request::post("/mcp")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::ACCEPT, accept);
+    if let Some(session_id) = session_id {
+        request = request.header(SESSION_HEADER, session_id);
+    }
+    request.body(Body::from(message.to_string())).unwrap()
+}
+
+fn initialize_request() -> Value {
+    json!({
+        "jsonrpc": "2.0", "id": 1, "method": "initialize",
+        "params": { "protocolVersion": LATEST_PROTOCOL_VERSION, "capabilities": {} }
+    })
+}
+
+async fn body_text(response: Response) -> String {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+async fn initialize(state: &AppState) -> String {
+    let response = api::router(state.clone())
+        .oneshot(post(None, "application/json", initialize_request()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    response.headers()[SESSION_HEADER].to_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn initialize_creates_shared_session() {
+    let state = AppState::new(VirtualFileSystem::new());
+    let session_id = initialize(&state).await;
+
+    let session = state.sessions.get(&session_id).unwrap();
+    assert_eq!(session.protocol_version.as_deref(), Some(LATEST_PROTOCOL_VERSION));
+}
+
+#[tokio::test]
+async fn requests_without_known_session_are_rejected() {
+    let state = AppState::new(VirtualFileSystem::new());
+    let list = json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" });
+
+    let missing = api::router(state.clone())
+        .oneshot(post(None, "application/json", list.clone()))
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), StatusCode::BAD_REQUEST);
+
+    let unknown = api::router(state.clone())
+        .oneshot(post(Some("nope"), "application/json", list))
+        .await
+        .unwrap();
+    assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+    assert!(state.sessions.is_empty());
+}
+
+#[tokio::test]
+async fn responses_stream_as_sse_when_accepted() {
+    let state = AppState::new(VirtualFileSystem::new());
+    let session_id = initialize(&state).await;
+
+    let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+    let accepted = api::router(state.clone())
+        .oneshot(post(Some(&session_id), "application/json, text/event-stream", notification))
+        .await
+        .unwrap();
+    assert_eq!(accepted.status(), StatusCode::ACCEPTED);
+
+    let list = json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" });
+    let response = api::router(state.clone())
+        .oneshot(post(Some(&session_id), "application/json, text/event-stream", list))
+        .await
+        .unwrap();
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "text/event-stream");
+
+    let text = body_text(response).await;
+    let data = text.lines().find_map(|line| line.strip_prefix("data: ")).unwrap();
+    let message: Value = serde_json::from_str(data).unwrap();
+    assert_eq!(message["id"], 2);
+    assert!(message["result"]["tools"].is_array());
+}
+
+#[tokio::test]
+async fn get_stream_delivers_session_messages() {
+    let state = AppState::new(VirtualFileSystem::new());
+    let session_id = initialize(&state).await;
+
+    let response = api::router(state.clone())
+        .oneshot(
+            Request::get("/mcp")
+                .header(header::ACCEPT, "text/event-stream")
+                .header(SESSION_HEADER, &session_id)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert!(state.send_to_session(&session_id, r#"{"jsonrpc":"2.0","method":"notifications/test"}"#.to_string()));
+    let mut body = response.into_body().into_data_stream();
+    let chunk = body.next().await.unwrap().unwrap();
+    assert!(String::from_utf8_lossy(&chunk).contains("notifications/test"));
+}
+
+#[tokio::test]
+async fn delete_terminates_session() {
+    let state = AppState::new(VirtualFileSystem::new());
+    let session_id = initialize(&state).await;
+
+    let delete = || {
+        Request::delete("/mcp")
+            .header(SESSION_HEADER, &session_id)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = api::router(state.clone()).oneshot(delete()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(state.sessions.is_empty());
+
+    let again = api::router(state.clone()).oneshot(delete()).await.unwrap();
+    assert_eq!(again.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test(start_paused = true)]
+async fn idle_sessions_without_streams_expire() {
+    let state = AppState::new(VirtualFileSystem::new());
+    state.config.write().await.api.timeout = 30;
+    let reaper = streamable_http::spawn_session_reaper(state.clone());
+    let (idle, active, streaming) = (initialize(&state).await, initialize(&state).await, initialize(&state).await);
+
+    let stream = api::router(state.clone())
+        .oneshot(
+            Request::get("/mcp")
+                .header(header::ACCEPT, "text/event-stream")
+                .header(SESSION_HEADER, &streaming)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(stream.status(), StatusCode::OK);
+
+    let ping = |id: u64| json!({ "jsonrpc": "2.0", "id": id, "method": "ping" });
+    tokio::time::sleep(Duration::from_secs(20)).await;
+    let response = api::router(state.clone()).oneshot(post(Some(&active), "application/json", ping(2))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The reaper runs at 30s: only the session that went quiet with no
+    // stream open is dropped
+    tokio::time::sleep(Duration::from_secs(15)).await;
+    assert!(!state.sessions.contains_key(&idle));
+    assert!(state.sessions.contains_key(&active));
+    assert!(state.sessions.contains_key(&streaming));
+    let response = api::router(state.clone()).oneshot(post(Some(&idle), "application/json", ping(3))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Once its stream closes, an idle session goes too
+    drop(stream);
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    assert!(!state.sessions.contains_key(&streaming));
+    assert!(!state.sessions.contains_key(&active));
+
+    reaper.abort();
+}
+
+#[tokio::test]
+async fn unsupported_protocol_version_header_is_rejected() {
+    let state = AppState::new(VirtualFileSystem::new());
+    let mut request = post(None, "application/json", initialize_request());
+    request.headers_mut().insert("mcp-protocol-version", "1999-01-01".parse().unwrap());
+
+    let response = api::router(state).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn shutdown_ends_open_get_streams() {
+    let state = AppState::new(VirtualFileSystem::new());
+    let session_id = initialize(&state).await;
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    state.config.write().await.api.port = port;
+
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(api::start_server_with_shutdown(state.clone(), async {
+        let _ = stopped.await;
+    }));
+
+    // Wait for the listener, then hold a GET stream open on a real connection
+    let mut stream = loop {
+        match TcpStream::connect(("127.0.0.1", port)).await {
+            Ok(stream) => break stream,
+            Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+        }
+    };
+    let request = format!(
+        "GET /mcp HTTP/1.1\r\nHost: localhost\r\nAccept: text/event-stream\r\n{}: {}\r\n\r\n",
+        SESSION_HEADER, session_id
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut head = [0; 64];
+    let read = stream.read(&mut head).await.unwrap();
+    assert!(String::from_utf8_lossy(&head[..read]).starts_with("HTTP/1.1 200"));
+
+    stop.send(()).unwrap();
+    let result = tokio::time::timeout(Duration::from_secs(5), server).await
+        .expect("server should stop while the stream is open");
+    result.unwrap().unwrap();
+}
@@ -0,0 +1,201 @@
+//! Range edits on rope-backed file content.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use abide_core::mcp::operations::FileDeleteRangeParams;
+use abide_core::mcp::{handle_mcp_request, McpRequest, McpResponse, Position};
+use abide_core::state::vfs::{TextEdit, TextPosition, TextRange};
+use abide_core::state::EditorSelection;
+use abide_core::{AppState, Error, VirtualFileSystem};
+use serde_json::json;
+
+fn offset(offset: usize) -> TextPosition {
+    TextPosition::Offset { offset }
+}
+
+fn line_column(line: u32, column: u32) -> TextPosition {
+    TextPosition::LineColumn(Position { line, column })
+}
+
+fn edit(start: (u32, u32), end: (u32, u32), new_text: &str) -> TextEdit {
+    TextEdit {
+        range: TextRange {
+            start: Position { line: start.0, column: start.1 },
+            end: Position { line: end.0, column: end.1 },
+        },
+        new_text: new_text.to_string(),
+    }
+}
+
+#[test]
+fn insert_delete_and_replace_ranges() {
+    let mut vfs = VirtualFileSystem::new();
+    let file_id = vfs.create_file(Path::new("/main.rs"), "fn main() {\n}\n".to_string()).unwrap();
+
+    vfs.insert_text(&file_id, &line_column(1, 0), "    println!(\"hi\");\n").unwrap();
+    vfs.insert_text(&file_id, &offset(0), "// é\n").unwrap();
+    assert_eq!(vfs.read_file(&file_id).unwrap().content, "// é\nfn main() {\n    println!(\"hi\");\n}\n");
+
+    let deleted = vfs.delete_range(&file_id, &offset(0), &line_column(1, 0)).unwrap();
+    assert_eq!(deleted, "// é\n");
+
+    vfs.replace_range(&file_id, &line_column(1, 14), &line_column(1, 16), "yo").unwrap();
+    assert_eq!(vfs.read_file(&file_id).unwrap().content, "fn main() {\n    println!(\"yo\");\n}\n");
+}
+
+#[test]
+fn positions_are_checked_against_the_content() {
+    let mut vfs = VirtualFileSystem::new();
+    let file_id = vfs.create_file(Path::new("/a.txt"), "ab\r\ncd".to_string()).unwrap();
+
+    // Columns stop before the line ending
+    vfs.insert_text(&file_id, &line_column(0, 2), "!").unwrap();
+    assert!(matches!(vfs.insert_text(&file_id, &line_column(0, 4), "x"), Err(Error::InvalidArgument(_))));
+    assert!(matches!(vfs.insert_text(&file_id, &line_column(2, 0), "x"), Err(Error::InvalidArgument(_))));
+    assert!(matches!(vfs.insert_text(&file_id, &offset(100), "x"), Err(Error::InvalidArgument(_))));
+    assert!(matches!(vfs.delete_range(&file_id, &offset(3), &offset(1)), Err(Error::InvalidArgument(_))));
+
+    vfs.insert_text(&file_id, &line_column(1, 2), "!").unwrap();
+    assert_eq!(vfs.read_file(&file_id).unwrap().content, "ab!\r\ncd!");
+}
+
+#[test]
+fn columns_count_utf16_code_units() {
+    let mut vfs = VirtualFileSystem::new();
+    let file_id = vfs.create_file(Path::new("/a.txt"), "a😀b\né".to_string()).unwrap();
+
+    // The emoji is two code units, as Monaco and LSP clients count it
+    vfs.insert_text(&file_id, &line_column(0, 3), "!").unwrap();
+    vfs.insert_text(&file_id, &line_column(1, 1), "?").unwrap();
+    assert_eq!(vfs.read_file(&file_id).unwrap().content, "a😀!b\né?");
+    assert!(matches!(vfs.insert_text(&file_id, &line_column(0, 2), "x"), Err(Error::InvalidArgument(_))));
+    assert!(matches!(vfs.insert_text(&file_id, &line_column(0, 6), "x"), Err(Error::InvalidArgument(_))));
+
+    let content = vfs.read_file(&file_id).unwrap().content.clone();
+    let selection = EditorSelection {
+        file_id,
+        start: Position { line: 0, column: 1 },
+        end: Position { line: 0, column: 4 },
+    };
+    assert_eq!(selection.text(&content).unwrap(), "😀!");
+}
+
+#[test]
+fn offsets_count_utf16_code_units() {
+    let mut vfs = VirtualFileSystem::new();
+    let file_id = vfs.create_file(Path::new("/a.txt"), "a😀b\nc".to_string()).unwrap();
+
+    // As Monaco's getOffsetAt counts: "a" is 0, the emoji 1-2, "b" 3
+    vfs.insert_text(&file_id, &offset(3), "!").unwrap();
+    vfs.delete_range(&file_id, &offset(6), &offset(7)).unwrap();
+    assert_eq!(vfs.read_file(&file_id).unwrap().content, "a😀!b\n");
+    assert!(matches!(vfs.insert_text(&file_id, &offset(2), "x"), Err(Error::InvalidArgument(_))));
+    assert!(matches!(vfs.insert_text(&file_id, &offset(7), "x"), Err(Error::InvalidArgument(_))));
+}
+
+#[test]
+fn only_lsp_line_endings_start_lines() {
+    let mut vfs = VirtualFileSystem::new();
+    let file_id = vfs.create_file(Path::new("/a.txt"), "x\x0Cy\nz\u{2028}w\rv\n".to_string()).unwrap();
+
+    // Form feeds and U+2028 are ordinary characters within a line
+    vfs.replace_range(&file_id, &line_column(1, 0), &line_column(1, 1), "Z").unwrap();
+    vfs.insert_text(&file_id, &line_column(0, 3), "!").unwrap();
+    vfs.insert_text(&file_id, &line_column(2, 1), "?").unwrap();
+    assert_eq!(vfs.read_file(&file_id).unwrap().content, "x\x0Cy!\nZ\u{2028}w\rv?\n");
+    assert!(matches!(vfs.insert_text(&file_id, &line_column(4, 0), "x"), Err(Error::InvalidArgument(_))));
+
+    // The editor selection reads positions the same way
+    let content = vfs.read_file(&file_id).unwrap().content.clone();
+    let selection = |start: (u32, u32), end: (u32, u32)| EditorSelection {
+        file_id: file_id.clone(),
+        start: Position { line: start.0, column: start.1 },
+        end: Position { line: end.0, column: end.1 },
+    };
+    assert_eq!(selection((1, 0), (1, 3)).text(&content).unwrap(), "Z\u{2028}w");
+    assert!(matches!(selection((1, 0), (1, 9)).text(&content), Err(Error::InvalidArgument(_))));
+    assert!(matches!(selection((0, 0), (7, 0)).text(&content), Err(Error::InvalidArgument(_))));
+}
+
+#[tokio::test]
+async fn range_edits_over_mcp() {
+    let state = Arc::new(AppState::new(VirtualFileSystem::new()));
+    state.vfs.write().await.create_file(Path::new("/a.js"), "let x = 1;".to_string()).unwrap();
+
+    let insert: McpRequest = serde_json::from_value(json!({
+        "type": "file.insert", "path": "/a.js", "at": { "line": 0, "column": 9 }, "text": "0"
+    })).unwrap();
+    assert!(matches!(handle_mcp_request(insert, &state).await, McpResponse::Success { .. }));
+
+    let replace: McpRequest = serde_json::from_value(json!({
+        "type": "file.replace_range", "id": "/a.js",
+        "start": { "offset": 4 }, "end": { "offset": 5 }, "text": "y"
+    })).unwrap();
+    assert!(matches!(handle_mcp_request(replace, &state).await, McpResponse::Success { .. }));
+
+    let delete = McpRequest::FileDeleteRange(FileDeleteRangeParams {
+        id: "/a.js".to_string(),
+        start: offset(0),
+        end: offset(4),
+        expected_version: None,
+    });
+    match handle_mcp_request(delete, &state).await {
+        McpResponse::Success { data } => assert_eq!(data["deleted"], "let "),
+        other => panic!("unexpected response: {:?}", other),
+    }
+
+    let vfs = state.vfs.read().await;
+    assert_eq!(vfs.file_by_path(Path::new("/a.js")).unwrap().content, "y = 10;");
+}
+
+#[test]
+fn edits_apply_together_against_the_original_text() {
+    let mut vfs = VirtualFileSystem::new();
+    let file_id = vfs.create_file(Path::new("/a.rs"), "let a = 1;\nlet b = 2;\n".to_string()).unwrap();
+    assert_eq!(vfs.read_file(&file_id).unwrap().version, 1);
+
+    let version = vfs.apply_edits(&file_id, &[
+        edit((1, 4), (1, 5), "bee"),
+        edit((0, 4), (0, 5), "ay"),
+        edit((0, 0), (0, 0), "// one\n"),
+        edit((0, 0), (0, 0), "// two\n"),
+    ]).unwrap();
+    assert_eq!(version, 2);
+    assert_eq!(vfs.read_file(&file_id).unwrap().content, "// one\n// two\nlet ay = 1;\nlet bee = 2;\n");
+}
+
+#[test]
+fn invalid_edits_change_nothing() {
+    let mut vfs = VirtualFileSystem::new();
+    let file_id = vfs.create_file(Path::new("/a.rs"), "abcdef".to_string()).unwrap();
+
+    let overlapping = vfs.apply_edits(&file_id, &[edit((0, 0), (0, 3), "x"), edit((0, 2), (0, 4), "y")]);
+    assert!(matches!(overlapping, Err(Error::InvalidArgument(_))));
+    let out_of_range = vfs.apply_edits(&file_id, &[edit((0, 0), (0, 1), "x"), edit((3, 0), (3, 0), "y")]);
+    assert!(matches!(out_of_range, Err(Error::InvalidArgument(_))));
+
+    let file = vfs.read_file(&file_id).unwrap();
+    assert_eq!(file.content, "abcdef");
+    assert_eq!(file.version, 1);
+}
+
+#[tokio::test]
+async fn edit_over_mcp_returns_the_new_version() {
+    let state = Arc::new(AppState::new(VirtualFileSystem::new()));
+    state.vfs.write().await.create_file(Path::new("/a.js"), "let x = 1;".to_string()).unwrap();
+
+    let request: McpRequest = serde_json::from_value(json!({
+        "type": "file.edit",
+        "path": "/a.js",
+        "edits": [{
+            "range": { "start": { "line": 0, "column": 8 }, "end": { "line": 0, "column": 9 } },
+            "newText": "42"
+        }]
+    })).unwrap();
+    match handle_mcp_request(request, &state).await {
+        McpResponse::Success { data } => assert_eq!(data["version"], 2),
+        other => panic!("unexpected response: {:?}", other),
+    }
+    assert_eq!(state.vfs.read().await.file_by_path(Path::new("/a.js")).unwrap().content, "let x = 42;");
+}
@@ -0,0 +1,61 @@
+//! Tool input schemas derived from the `McpRequest` parameter structs.
+
+use abide_core::api;
+use abide_core::mcp::tools::list_tools;
+use abide_core::{AppState, VirtualFileSystem};
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+fn schema(name: &str) -> Value {
+    list_tools()
+        .into_iter()
+        .find(|tool| tool.name == name)
+        .unwrap_or_else(|| panic!("missing tool {}", name))
+        .input_schema
+}
+
+#[test]
+fn schemas_follow_request_structs() {
+    let create = schema("file_create");
+    assert_eq!(create["type"], "object");
+    assert_eq!(create["required"], json!(["path", "content"]));
+    assert_eq!(create["properties"]["path"]["type"], "string");
+    assert!(create["properties"]["path"]["description"].is_string());
+
+    let typing = schema("animation_typing");
+    assert_eq!(typing["required"], json!(["file_id", "content"]));
+
+    // Nested types are inlined rather than referenced.
+    let cursor = schema("animation_cursor");
+    assert_eq!(cursor["properties"]["from"]["type"], "object");
+    assert_eq!(cursor["properties"]["to"]["required"], json!(["line", "column"]));
+    assert!(cursor.get("$defs").is_none());
+
+    assert_eq!(schema("settings_get")["type"], "object");
+}
+
+#[tokio::test]
+async fn rest_endpoint_serves_tool_definitions() {
+    let state = AppState::new(VirtualFileSystem::new());
+    let response = api::router(state)
+        .oneshot(Request::get("/api/tools").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    let tools = body["tools"].as_array().unwrap();
+    assert_eq!(tools.len(), list_tools().len());
+    assert_eq!(tools[0]["inputSchema"], schema("file_create"));
+}
+
+#[test]
+fn text_positions_accept_offsets_or_line_columns() {
+    let insert = schema("file_insert");
+    let variants = insert["properties"]["at"]["anyOf"].as_array().unwrap();
+    assert_eq!(variants[0]["required"], json!(["offset"]));
+    assert_eq!(variants[1]["required"], json!(["line", "column"]));
+}
@@ -0,0 +1,115 @@
+//! Optimistic concurrency: expected versions over MCP, ETags over REST.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use abide_core::api;
+use abide_core::mcp::{handle_mcp_request, McpRequest, McpResponse};
+use abide_core::state::batch::BatchOperation;
+use abide_core::{AppState, Error, VirtualFileSystem};
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+#[test]
+fn stale_versions_conflict_with_the_current_content() {
+    let mut vfs = VirtualFileSystem::new();
+    let file_id = vfs.create_file(Path::new("/a.txt"), "one".to_string()).unwrap();
+    vfs.check_version(&file_id, Some(1)).unwrap();
+    vfs.write_file(&file_id, "two".to_string()).unwrap();
+    vfs.check_version(&file_id, None).unwrap();
+
+    match vfs.check_version(&file_id, Some(1)) {
+        Err(Error::VersionConflict { file, expected, current, content }) => {
+            assert_eq!((file.as_str(), expected, current, content.as_str()), ("/a.txt", 1, 2, "two"));
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let stale: Vec<BatchOperation> = serde_json::from_value(json!([
+        { "op": "update", "id": "/a.txt", "content": "three", "expected_version": 1 },
+    ])).unwrap();
+    assert!(matches!(vfs.apply_batch(&stale), Err(Error::BatchFailed(_))));
+    assert_eq!(vfs.read_file(&file_id).unwrap().content, "two");
+}
+
+#[tokio::test]
+async fn mcp_updates_check_the_expected_version() {
+    let state = Arc::new(AppState::new(VirtualFileSystem::new()));
+    state.vfs.write().await.create_file(Path::new("/a.js"), "let a = 1;".to_string()).unwrap();
+    let run = |request: Value| {
+        let state = state.clone();
+        async move { handle_mcp_request(serde_json::from_value::<McpRequest>(request).unwrap(), &state).await }
+    };
+
+    match run(json!({ "type": "file.update", "id": "/a.js", "content": "let a = 2;", "expected_version": 1 })).await {
+        McpResponse::Success { data } => assert_eq!(data["version"], 2),
+        other => panic!("unexpected response: {:?}", other),
+    }
+
+    // Another session saw version 1 and tries to edit it
+    let stale = run(json!({
+        "type": "file.insert", "id": "/a.js", "at": { "offset": 0 }, "text": "// ", "expected_version": 1
+    })).await;
+    match stale {
+        McpResponse::Error { code, data, .. } => {
+            assert_eq!(code, -32023);
+            assert_eq!(data["kind"], "version_conflict");
+            assert_eq!(data["current"], 2);
+            assert_eq!(data["content"], "let a = 2;");
+        }
+        other => panic!("unexpected response: {:?}", other),
+    }
+    assert_eq!(state.vfs.read().await.file_by_path(Path::new("/a.js")).unwrap().content, "let a = 2;");
+
+    // Range edits report their new version, so they can be chained
+    for (version, mut request) in (2..).zip([
+        json!({ "type": "file.insert", "id": "/a.js", "at": { "offset": 0 }, "text": "// " }),
+        json!({ "type": "file.delete_range", "id": "/a.js", "start": { "offset": 0 }, "end": { "offset": 3 } }),
+        json!({ "type": "file.replace_range", "id": "/a.js", "start": { "offset": 8 }, "end": { "offset": 9 }, "text": "3" }),
+    ]) {
+        request["expected_version"] = json!(version);
+        match run(request).await {
+            McpResponse::Success { data } => assert_eq!(data["version"], version + 1),
+            other => panic!("unexpected response: {:?}", other),
+        }
+    }
+    assert_eq!(state.vfs.read().await.file_by_path(Path::new("/a.js")).unwrap().content, "let a = 3;");
+}
+
+#[tokio::test]
+async fn rest_files_carry_etags_and_honour_if_match() {
+    let state = AppState::new(VirtualFileSystem::new());
+    state.vfs.write().await.create_file(Path::new("/a.txt"), "one".to_string()).unwrap();
+    let send = |request: Request<Body>| api::router(state.clone()).oneshot(request);
+    let update = |if_match: &str, content: &str| {
+        Request::post("/api/files/a.txt")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::IF_MATCH, if_match)
+            .body(Body::from(json!({ "content": content }).to_string()))
+            .unwrap()
+    };
+
+    let read = send(Request::get("/api/files/a.txt").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(read.headers()[header::ETAG], "\"1\"");
+
+    let updated = send(update("\"1\"", "two")).await.unwrap();
+    assert_eq!(updated.status(), StatusCode::OK);
+    assert_eq!(updated.headers()[header::ETAG], "\"2\"");
+
+    let stale = send(update("\"1\"", "lost")).await.unwrap();
+    assert_eq!(stale.status(), StatusCode::CONFLICT);
+    let bytes = axum::body::to_bytes(stale.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!((body["kind"].clone(), body["content"].clone()), (json!("version_conflict"), json!("two")));
+
+    assert_eq!(send(update("\"7\", \"2\"", "three")).await.unwrap().status(), StatusCode::OK);
+    assert_eq!(send(update("*", "four")).await.unwrap().status(), StatusCode::OK);
+    assert_eq!(send(update("W/\"4\"", "five")).await.unwrap().status(), StatusCode::OK);
+    assert_eq!(send(update("v5", "six")).await.unwrap().status(), StatusCode::BAD_REQUEST);
+
+    let delete = |if_match: &str| Request::delete("/api/files/a.txt").header(header::IF_MATCH, if_match).body(Body::empty()).unwrap();
+    assert_eq!(send(delete("\"3\"")).await.unwrap().status(), StatusCode::CONFLICT);
+    assert_eq!(send(delete("\"5\"")).await.unwrap().status(), StatusCode::NO_CONTENT);
+}
@@ -0,0 +1,237 @@
+//! Structural VFS operations on files and directory subtrees.
+
+use std::path::Path;
+
+use abide_core::api;
+use abide_core::state::vfs::{normalize_path, ConflictPolicy};
+use abide_core::{AppState, Error, VirtualFileSystem};
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+fn project() -> (VirtualFileSystem, String) {
+    let mut vfs = VirtualFileSystem::new();
+    vfs.create_directory(Path::new("/src")).unwrap();
+    vfs.create_directory(Path::new("/src/util")).unwrap();
+    let file_id = vfs.create_file(Path::new("/src/util/math.js"), "export {}".to_string()).unwrap();
+    (vfs, file_id)
+}
+
+#[test]
+fn move_rebases_subtree_and_keeps_ids() {
+    let (mut vfs, file_id) = project();
+    vfs.create_directory(Path::new("/lib")).unwrap();
+    let created_at = vfs.read_file(&file_id).unwrap().created_at;
+
+    let moved = vfs.move_node(Path::new("/src/util"), Path::new("/lib/helpers")).unwrap();
+    assert_eq!(moved.path, Path::new("/lib/helpers"));
+    assert_eq!(moved.children[0].path, Path::new("/lib/helpers/math.js"));
+
+    assert_eq!(vfs.resolve_file("/lib/helpers/math.js").unwrap(), file_id);
+    assert!(vfs.resolve_file("/src/util/math.js").is_err());
+    assert_eq!(vfs.read_file(&file_id).unwrap().created_at, created_at);
+    assert!(vfs.list_directory(Path::new("/src")).unwrap().is_empty());
+}
+
+#[test]
+fn rename_redetects_language() {
+    let (mut vfs, file_id) = project();
+
+    vfs.rename(Path::new("/src/util/math.js"), "math.ts").unwrap();
+    let file = vfs.read_file(&file_id).unwrap();
+    assert_eq!(file.name, "math.ts");
+    assert_eq!(file.language.as_deref(), Some("typescript"));
+    assert!(vfs.rename(Path::new("/src/util/math.ts"), "a/b.ts").is_err());
+}
+
+#[test]
+fn move_rejects_conflicts_and_cycles() {
+    let (mut vfs, _) = project();
+    vfs.create_file(Path::new("/src/main.js"), String::new()).unwrap();
+
+    let conflict = vfs.move_node(Path::new("/src/main.js"), Path::new("/src/util/math.js")).unwrap_err();
+    assert!(matches!(conflict, Error::AlreadyExists(_)));
+    assert!(vfs.move_node(Path::new("/src"), Path::new("/src/util/src")).is_err());
+    assert!(vfs.move_node(Path::new("/missing.js"), Path::new("/other.js")).is_err());
+}
+
+#[tokio::test]
+async fn rest_move_reports_conflicts() {
+    let (vfs, _) = project();
+    let state = AppState::new(vfs);
+
+    let post = |uri: &str, body: Value| {
+        Request::post(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let renamed = api::router(state.clone())
+        .oneshot(post("/api/nodes/rename", json!({ "path": "/src/util", "new_name": "lib" })))
+        .await
+        .unwrap();
+    assert_eq!(renamed.status(), StatusCode::OK);
+
+    let conflict = api::router(state.clone())
+        .oneshot(post("/api/nodes/move", json!({ "from": "/src/lib/math.js", "to": "/src/lib/math.js" })))
+        .await
+        .unwrap();
+    assert_eq!(conflict.status(), StatusCode::CONFLICT);
+
+    assert!(state.vfs.read().await.resolve_file("/src/lib/math.js").is_ok());
+}
+
+#[test]
+fn copy_clones_subtree_with_fresh_ids() {
+    let (mut vfs, file_id) = project();
+
+    let report = vfs.copy_node(Path::new("/src/util"), Path::new("/src/shared"), ConflictPolicy::Fail).unwrap();
+    assert_eq!(report.created, [Path::new("/src/shared"), Path::new("/src/shared/math.js")]);
+
+    let copy_id = vfs.resolve_file("/src/shared/math.js").unwrap();
+    assert_ne!(copy_id, file_id);
+    assert_eq!(vfs.read_file(&copy_id).unwrap().content, "export {}");
+
+    vfs.write_file(&copy_id, "changed".to_string()).unwrap();
+    assert_eq!(vfs.read_file(&file_id).unwrap().content, "export {}");
+}
+
+#[test]
+fn copy_conflict_policies() {
+    let (mut vfs, file_id) = project();
+    vfs.create_directory(Path::new("/lib")).unwrap();
+    let existing = vfs.create_file(Path::new("/lib/math.js"), "old".to_string()).unwrap();
+    vfs.create_file(Path::new("/src/util/extra.js"), "extra".to_string()).unwrap();
+
+    let failed = vfs.copy_node(Path::new("/src/util"), Path::new("/lib"), ConflictPolicy::Fail).unwrap_err();
+    assert!(matches!(failed, Error::AlreadyExists(_)));
+    assert!(vfs.resolve_file("/lib/extra.js").is_err());
+
+    let skipped = vfs.copy_node(Path::new("/src/util"), Path::new("/lib"), ConflictPolicy::Skip).unwrap();
+    assert_eq!(skipped.skipped, [Path::new("/lib/math.js")]);
+    assert_eq!(skipped.created, [Path::new("/lib/extra.js")]);
+    assert_eq!(vfs.read_file(&existing).unwrap().content, "old");
+
+    let overwritten = vfs.copy_node(Path::new("/src/util/math.js"), Path::new("/lib/math.js"), ConflictPolicy::Overwrite).unwrap();
+    assert_eq!(overwritten.overwritten, [Path::new("/lib/math.js")]);
+    assert_eq!(vfs.read_file(&existing).unwrap().content, vfs.read_file(&file_id).unwrap().content);
+
+    // A file never replaces a directory, whatever the policy.
+    assert!(vfs.copy_node(Path::new("/src/util/math.js"), Path::new("/lib"), ConflictPolicy::Overwrite).is_err());
+}
+
+#[test]
+fn directory_deletion_removes_descendant_files() {
+    let (mut vfs, file_id) = project();
+
+    let not_empty = vfs.delete_directory(Path::new("/src"), false).unwrap_err();
+    assert!(not_empty.to_string().contains("not empty"));
+
+    let report = vfs.delete_directory(Path::new("/src"), true).unwrap();
+    assert_eq!(report.removed_directories, [Path::new("/src"), Path::new("/src/util")]);
+    assert_eq!(report.removed_files, [Path::new("/src/util/math.js")]);
+    assert!(vfs.read_file(&file_id).is_err());
+    assert!(vfs.files().is_empty());
+    assert!(vfs.list_directory(Path::new("/")).unwrap().is_empty());
+
+    vfs.create_directory(Path::new("/empty")).unwrap();
+    assert!(vfs.delete_directory(Path::new("/empty"), false).is_ok());
+    assert!(vfs.delete_directory(Path::new("/"), true).is_err());
+}
+
+#[tokio::test]
+async fn rest_directory_delete_takes_recursive_flag() {
+    let (vfs, _) = project();
+    let state = AppState::new(vfs);
+    let delete = |uri: &str| Request::delete(uri).body(Body::empty()).unwrap();
+
+    let refused = api::router(state.clone()).oneshot(delete("/api/directories/src")).await.unwrap();
+    assert_eq!(refused.status(), StatusCode::CONFLICT);
+
+    let deleted = api::router(state.clone()).oneshot(delete("/api/directories/src?recursive=true")).await.unwrap();
+    assert_eq!(deleted.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(deleted.into_body(), usize::MAX).await.unwrap();
+    let report: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(report["removed_files"], json!(["/src/util/math.js"]));
+}
+
+#[test]
+fn paths_are_normalized_against_a_single_root() {
+    assert_eq!(normalize_path(Path::new("/src/./util/../main.js")).unwrap(), Path::new("/src/main.js"));
+    assert_eq!(normalize_path(Path::new("src/main.js")).unwrap(), Path::new("/src/main.js"));
+    assert_eq!(normalize_path(Path::new("/")).unwrap(), Path::new("/"));
+    assert!(normalize_path(Path::new("/../etc/passwd")).is_err());
+    assert!(normalize_path(Path::new("src/../../x")).is_err());
+
+    let (mut vfs, file_id) = project();
+    assert_eq!(vfs.resolve_file("/src/util/../util/./math.js").unwrap(), file_id);
+    assert!(vfs.create_file(Path::new("/../escape.js"), String::new()).is_err());
+}
+
+#[test]
+fn duplicate_names_are_rejected() {
+    let (mut vfs, _) = project();
+
+    let file = vfs.create_file(Path::new("/src/util/math.js"), String::new()).unwrap_err();
+    assert!(matches!(file, Error::AlreadyExists(_)));
+    let dir = vfs.create_directory(Path::new("/src/./util")).unwrap_err();
+    assert!(matches!(dir, Error::AlreadyExists(_)));
+    assert_eq!(vfs.files().len(), 1);
+    assert_eq!(vfs.list_directory(Path::new("/src")).unwrap().len(), 1);
+}
+
+#[test]
+fn parents_option_creates_missing_directories() {
+    let mut vfs = VirtualFileSystem::new();
+    assert!(vfs.create_file(Path::new("/a/b/c.js"), String::new()).is_err());
+
+    let file_id = vfs.create_file_all(Path::new("/a/b/c.js"), String::new()).unwrap();
+    assert_eq!(vfs.resolve_file("/a/b/c.js").unwrap(), file_id);
+
+    // Like `mkdir -p`, an existing directory is fine and keeps its id.
+    let dir_id = vfs.create_directory_all(Path::new("/a/b")).unwrap();
+    assert_eq!(vfs.list_directory(Path::new("/a")).unwrap()[0].id, dir_id);
+    assert!(vfs.create_directory_all(Path::new("/a/b/c.js/d")).is_err());
+}
+
+#[test]
+fn path_index_follows_moves_and_deletes() {
+    let (mut vfs, file_id) = project();
+
+    vfs.move_node(Path::new("/src"), Path::new("/app")).unwrap();
+    assert_eq!(vfs.path_of(&file_id), Some(Path::new("/app/util/math.js")));
+    assert!(vfs.list_directory(Path::new("/src/util")).is_err());
+    // The old paths are free again
+    vfs.create_file_all(Path::new("/src/util/math.js"), String::new()).unwrap();
+
+    vfs.delete_directory(Path::new("/app"), true).unwrap();
+    assert_eq!(vfs.path_of(&file_id), None);
+    assert!(vfs.resolve_file("/app/util/math.js").is_err());
+    assert_eq!(vfs.files().len(), 1);
+}
+
+#[test]
+fn files_cannot_hold_children() {
+    let (mut vfs, _) = project();
+
+    let nested = vfs.create_file(Path::new("/src/util/math.js/inner.js"), String::new()).unwrap_err();
+    assert!(matches!(nested, Error::NotADirectory(_)));
+    assert!(vfs.create_directory(Path::new("/src/util/math.js/dir")).is_err());
+}
+
+#[test]
+fn listing_is_one_level_and_tree_is_the_whole_subtree() {
+    let (vfs, file_id) = project();
+
+    let listed = vfs.list_directory(Path::new("/")).unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].path, Path::new("/src"));
+    assert!(listed[0].children.is_empty());
+
+    let tree = vfs.tree(Path::new("/")).unwrap();
+    let util = &tree.children[0].children[0];
+    assert_eq!(util.path, Path::new("/src/util"));
+    assert_eq!(util.children[0].file_id.as_deref(), Some(file_id.as_str()));
+}
@@ -0,0 +1,138 @@
+//! Saving the VFS to a workspace file, restoring it and autosaving.
+
+use std::path::Path;
+use std::time::Duration;
+
+use abide_core::api;
+use abide_core::state::workspace::{self, WORKSPACE_FILE_NAME};
+use abide_core::{AppConfig, AppState, Error, VirtualFileSystem};
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+fn project() -> VirtualFileSystem {
+    let mut vfs = VirtualFileSystem::new();
+    let main = vfs.create_file_all(Path::new("/src/main.rs"), "fn main() {}\n".to_string()).unwrap();
+    vfs.write_file(&main, "fn main() {\n    run();\n}\n".to_string()).unwrap();
+    vfs.create_file_all(Path::new("/docs/README.md"), "# Docs\n".to_string()).unwrap();
+    vfs.create_directory_all(Path::new("/empty/nested")).unwrap();
+    vfs
+}
+
+#[test]
+fn saved_workspaces_load_back_unchanged() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(WORKSPACE_FILE_NAME);
+    let vfs = project();
+
+    workspace::save(&vfs, &path).unwrap();
+    let loaded = workspace::load(&path).unwrap().unwrap();
+
+    let (saved, restored) = (vfs.to_workspace(), loaded.to_workspace());
+    assert_eq!(restored.root_id, saved.root_id);
+    assert_eq!(
+        serde_json::to_value(&restored.nodes).unwrap(),
+        serde_json::to_value(&saved.nodes).unwrap()
+    );
+
+    let main = loaded.file_by_path(Path::new("/src/main.rs")).unwrap();
+    assert_eq!(main.version, 2);
+    assert_eq!(main.language.as_deref(), Some("rust"));
+    assert_eq!(loaded.history(&main.id).unwrap().revisions.len(), 1);
+    assert!(loaded.list_directory(Path::new("/empty/nested")).unwrap().is_empty());
+}
+
+#[test]
+fn saves_replace_the_file_without_leaving_temporaries() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("data").join(WORKSPACE_FILE_NAME);
+    assert!(workspace::load(&path).unwrap().is_none());
+
+    let mut vfs = project();
+    workspace::save(&vfs, &path).unwrap();
+    vfs.create_file(Path::new("/later.txt"), "later".to_string()).unwrap();
+    workspace::save(&vfs, &path).unwrap();
+
+    let entries: Vec<_> = std::fs::read_dir(path.parent().unwrap()).unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(entries, [WORKSPACE_FILE_NAME]);
+    let loaded = workspace::load(&path).unwrap().unwrap();
+    assert_eq!(loaded.file_by_path(Path::new("/later.txt")).unwrap().content, "later");
+}
+
+#[test]
+fn unknown_formats_and_broken_files_are_not_loaded() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(WORKSPACE_FILE_NAME);
+
+    std::fs::write(&path, json!({ "format": 99, "nodes": "whatever" }).to_string()).unwrap();
+    assert!(matches!(workspace::load(&path), Err(Error::InvalidArgument(_))));
+
+    // On launch a broken file is moved aside rather than overwritten later
+    std::fs::write(&path, "{ not json").unwrap();
+    assert!(matches!(workspace::load(&path), Err(Error::Internal(_))));
+    let vfs = workspace::restore(&path).unwrap();
+    assert!(vfs.files().is_empty());
+    assert!(!path.exists());
+    assert_eq!(std::fs::read_to_string(dir.path().join("workspace.json.corrupt")).unwrap(), "{ not json");
+}
+
+#[test]
+fn unreadable_files_are_reported_and_left_in_place() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(WORKSPACE_FILE_NAME);
+
+    // A directory can't be read as a file whoever runs the test
+    std::fs::create_dir(&path).unwrap();
+    assert!(matches!(workspace::restore(&path), Err(Error::Internal(_))));
+    assert!(path.is_dir());
+    assert!(!dir.path().join("workspace.json.corrupt").exists());
+}
+
+#[tokio::test(start_paused = true)]
+async fn autosave_follows_the_editor_settings() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(WORKSPACE_FILE_NAME);
+    let mut config = AppConfig::default();
+    config.editor.auto_save = false;
+    config.editor.auto_save_delay = 50;
+    config.workspace.path = Some(path.clone());
+    let state = AppState::with_config(VirtualFileSystem::new(), config);
+    let autosave = workspace::spawn_autosave(state.clone());
+
+    // The clock is paused, so each sleep jumps past a few autosave checks
+    // at once instead of waiting for them
+    state.vfs.write().await.create_file(Path::new("/a.txt"), "one".to_string()).unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(!path.exists());
+
+    state.config.write().await.editor.auto_save = true;
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let loaded = workspace::load(&path).unwrap().unwrap();
+    assert_eq!(loaded.file_by_path(Path::new("/a.txt")).unwrap().content, "one");
+
+    autosave.abort();
+}
+
+#[tokio::test]
+async fn rest_save_writes_the_configured_workspace() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(WORKSPACE_FILE_NAME);
+    let state = AppState::new(project());
+    let save = || Request::post("/api/project").body(Body::empty()).unwrap();
+
+    let unconfigured = api::router(state.clone()).oneshot(save()).await.unwrap();
+    assert_eq!(unconfigured.status(), StatusCode::BAD_REQUEST);
+
+    state.config.write().await.workspace.path = Some(path.clone());
+    let saved = api::router(state.clone()).oneshot(save()).await.unwrap();
+    assert_eq!(saved.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(saved.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["files"], 2);
+
+    let loaded = workspace::load(&path).unwrap().unwrap();
+    assert_eq!(loaded.file_by_path(Path::new("/docs/README.md")).unwrap().content, "# Docs\n");
+}
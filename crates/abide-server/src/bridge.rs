@@ -0,0 +1,52 @@
+//! Bridges an MCP stdio session onto the WebSocket endpoint of a running
+//! ABIDE instance, so desktop LLM hosts share the app's VFS.
+
+use anyhow::{Context, Result};
+use futures::{SinkExt, StreamExt};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{error, info};
+
+use abide_core::mcp::stdio::write_line;
+
+pub async fn run(url: &str) -> Result<()> {
+    let (socket, _) = tokio_tungstenite::connect_async(url)
+        .await
+        .with_context(|| format!("Failed to connect to ABIDE at {}", url))?;
+    info!("Bridging stdio to {}", url);
+
+    let (mut sink, mut stream) = socket.split();
+    let mut stdin = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    let mut stdin_open = true;
+
+    loop {
+        tokio::select! {
+            line = stdin.next_line(), if stdin_open => match line? {
+                Some(line) if line.trim().is_empty() => {}
+                Some(line) => sink.send(Message::Text(line)).await?,
+                None => {
+                    // Keep reading until the server completes the close
+                    // handshake so responses already in flight still arrive.
+                    info!("stdin closed, disconnecting from {}", url);
+                    stdin_open = false;
+                    sink.send(Message::Close(None)).await?;
+                }
+            },
+            message = stream.next() => match message {
+                Some(Ok(Message::Text(text))) => write_line(&mut stdout, &text).await?,
+                Some(Ok(Message::Close(_))) | None => {
+                    info!("ABIDE closed the connection");
+                    break;
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    error!("WebSocket error: {}", e);
+                    return Err(e.into());
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
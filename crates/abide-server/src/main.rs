@@ -0,0 +1,140 @@
+//! Headless ABIDE: runs the HTTP/MCP API server without the Tauri window.
+//!
+//! Usage: `abide-server [--host <host>] [--port <port>] [--prompts-dir <dir>] [--workspace <file>] [--stdio | --bridge]`
+//!
+//! With `--workspace` the VFS is restored from that file on start, autosaved
+//! to it as configured in `editor.auto_save`, and saved once more on exit.
+//!
+//! `--stdio` speaks MCP over stdin/stdout with its own VFS instead of
+//! listening on HTTP, until stdin closes or SIGINT/SIGTERM. `--bridge` also speaks MCP over stdio, but forwards
+//! every message to the `/mcp` endpoint of an ABIDE instance already
+//! running on `--host`/`--port`.
+
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use tracing::{error, info};
+
+use abide_core::mcp;
+use abide_core::state::{workspace, AppConfig};
+use abide_core::utils::logging::{init_logging, init_stderr_logging};
+use abide_core::{api, AppState, VirtualFileSystem};
+
+mod bridge;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Mode {
+    Http,
+    Stdio,
+    Bridge,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut config = AppConfig::default();
+    let mode = apply_args(&mut config, std::env::args().skip(1))?;
+
+    match mode {
+        Mode::Http => {
+            init_logging();
+            info!("Starting ABIDE headless server");
+            let app_state = restore(config)?;
+            let result = api::start_server_with_shutdown(app_state.clone(), shutdown_signal()).await;
+            save_on_exit(&app_state).await;
+            result
+        }
+        Mode::Stdio => {
+            init_stderr_logging();
+            info!("Starting ABIDE MCP stdio server");
+            let app_state = restore(config)?;
+            // Hosts may stop the server with a signal rather than closing stdin
+            tokio::select! {
+                result = mcp::stdio::serve(tokio::io::stdin(), tokio::io::stdout(), Arc::new(app_state.clone())) => result?,
+                _ = shutdown_signal() => {}
+            }
+            save_on_exit(&app_state).await;
+            Ok(())
+        }
+        Mode::Bridge => {
+            init_stderr_logging();
+            let url = format!("ws://{}:{}/mcp", config.api.host, config.api.port);
+            bridge::run(&url).await
+        }
+    }
+}
+
+/// Builds the app state from the workspace file, if one is configured, and
+/// starts autosaving to it.
+fn restore(config: AppConfig) -> Result<AppState> {
+    let Some(path) = config.workspace.path.clone() else {
+        return Ok(AppState::with_config(VirtualFileSystem::new(), config));
+    };
+
+    let app_state = AppState::with_config(workspace::restore(&path)?, config);
+    workspace::spawn_autosave(app_state.clone());
+    Ok(app_state)
+}
+
+async fn save_on_exit(app_state: &AppState) {
+    if app_state.config.read().await.workspace.path.is_none() {
+        return;
+    }
+    match workspace::save_state(app_state).await {
+        Ok(report) => info!("Saved workspace to {}", report.path.display()),
+        Err(e) => error!("Failed to save workspace: {}", e),
+    }
+}
+
+fn apply_args(config: &mut AppConfig, mut args: impl Iterator<Item = String>) -> Result<Mode> {
+    let mut mode = Mode::Http;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--host" => {
+                config.api.host = args.next().ok_or_else(|| anyhow!("--host requires a value"))?;
+            }
+            "--port" => {
+                let port = args.next().ok_or_else(|| anyhow!("--port requires a value"))?;
+                config.api.port = port.parse().with_context(|| format!("Invalid port: {}", port))?;
+            }
+            "--prompts-dir" => {
+                let dir = args.next().ok_or_else(|| anyhow!("--prompts-dir requires a value"))?;
+                config.mcp.prompts_dir = Some(dir.into());
+            }
+            "--workspace" => {
+                let file = args.next().ok_or_else(|| anyhow!("--workspace requires a value"))?;
+                config.workspace.path = Some(file.into());
+            }
+            "--stdio" if mode == Mode::Http => mode = Mode::Stdio,
+            "--bridge" if mode == Mode::Http => mode = Mode::Bridge,
+            "--stdio" | "--bridge" => return Err(anyhow!("--stdio and --bridge are mutually exclusive")),
+            other => return Err(anyhow!("Unknown argument: {}", other)),
+        }
+    }
+
+    Ok(mode)
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install SIGINT handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received SIGINT, shutting down"),
+        _ = terminate => info!("Received SIGTERM, shutting down"),
+    }
+}
@@ -0,0 +1,14 @@
+use tauri::State;
+
+use abide_core::state::{AppState, EditorSelection};
+use abide_core::Error;
+
+/// Records the editor selection so MCP prompts can refer to it.
+#[tauri::command]
+pub async fn set_editor_selection(
+    selection: Option<EditorSelection>,
+    state: State<'_, AppState>,
+) -> Result<(), Error> {
+    *state.selection.write().await = selection;
+    Ok(())
+}
@@ -1,9 +1,8 @@
 use tauri::State;
 use serde::{Serialize, Deserialize};
-use std::sync::Arc;
-use tokio::sync::RwLock;
 
-use crate::state::VirtualFileSystem;
+use abide_core::state::{AppState, Author};
+use abide_core::Error;
 
 #[derive(Serialize, Deserialize)]
 pub struct FileInfo {
@@ -11,68 +10,139 @@ pub struct FileInfo {
     pub name: String,
     pub content: String,
     pub language: Option<String>,
+    pub version: u64,
 }
 
 #[tauri::command]
 pub async fn read_file(
     file_id: String,
-    vfs: State<'_, Arc<RwLock<VirtualFileSystem>>>,
-) -> Result<FileInfo, String> {
-    let vfs = vfs.read().await;
+    state: State<'_, AppState>,
+) -> Result<FileInfo, Error> {
+    let vfs = state.vfs.read().await;
     
-    match vfs.read_file(&file_id) {
+    match vfs.resolve_file(&file_id).and_then(|id| vfs.read_file(&id)) {
         Ok(file) => Ok(FileInfo {
             id: file.id.clone(),
             name: file.name.clone(),
-            content: file.content.clone(),
+            content: file.content.to_string(),
             language: file.language.clone(),
+            version: file.version,
         }),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(e),
     }
 }
 
+/// Saves a file and returns its new version. With `expected_version`, the
+/// save fails with a version conflict if the file changed in the meantime.
 #[tauri::command]
 pub async fn write_file(
     file_id: String,
     content: String,
-    vfs: State<'_, Arc<RwLock<VirtualFileSystem>>>,
-) -> Result<(), String> {
-    let mut vfs = vfs.write().await;
+    expected_version: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<u64, Error> {
+    let mut vfs = state.vfs_as(&Author::user()).await;
     
-    vfs.write_file(&file_id, content)
-        .map_err(|e| e.to_string())
+    vfs.resolve_file(&file_id).and_then(|id| {
+        vfs.check_version(&id, expected_version)?;
+        vfs.write_file(&id, content)?;
+        Ok(vfs.read_file(&id)?.version)
+    })
 }
 
 #[tauri::command]
 pub async fn create_file(
     path: String,
     content: String,
-    vfs: State<'_, Arc<RwLock<VirtualFileSystem>>>,
-) -> Result<String, String> {
-    let mut vfs = vfs.write().await;
+    parents: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<String, Error> {
+    let mut vfs = state.vfs_as(&Author::user()).await;
+    let path = std::path::Path::new(&path);
     
-    vfs.create_file(&std::path::Path::new(&path), content)
-        .map_err(|e| e.to_string())
+    if parents.unwrap_or(false) {
+        vfs.create_file_all(path, content)
+    } else {
+        vfs.create_file(path, content)
+    }
+}
+
+#[tauri::command]
+pub async fn create_directory(
+    path: String,
+    parents: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<String, Error> {
+    let mut vfs = state.vfs_as(&Author::user()).await;
+    let path = std::path::Path::new(&path);
+    
+    if parents.unwrap_or(false) {
+        vfs.create_directory_all(path)
+    } else {
+        vfs.create_directory(path)
+    }
 }
 
 #[tauri::command]
 pub async fn delete_file(
     file_id: String,
-    vfs: State<'_, Arc<RwLock<VirtualFileSystem>>>,
-) -> Result<(), String> {
-    let mut vfs = vfs.write().await;
+    state: State<'_, AppState>,
+) -> Result<(), Error> {
+    let mut vfs = state.vfs_as(&Author::user()).await;
     
-    vfs.delete_file(&file_id)
-        .map_err(|e| e.to_string())
+    vfs.resolve_file(&file_id)
+        .and_then(|id| vfs.delete_file(&id))
+}
+
+#[tauri::command]
+pub async fn delete_directory(
+    path: String,
+    recursive: bool,
+    state: State<'_, AppState>,
+) -> Result<abide_core::state::vfs::DeleteReport, Error> {
+    let mut vfs = state.vfs_as(&Author::user()).await;
+    
+    vfs.delete_directory(std::path::Path::new(&path), recursive)
 }
 
 #[tauri::command]
 pub async fn list_directory(
     path: String,
-    vfs: State<'_, Arc<RwLock<VirtualFileSystem>>>,
-) -> Result<Vec<crate::state::FileNode>, String> {
-    let vfs = vfs.read().await;
+    state: State<'_, AppState>,
+) -> Result<Vec<abide_core::state::FileNode>, Error> {
+    let vfs = state.vfs.read().await;
+    
+    vfs.list_directory(std::path::Path::new(&path))
+}
+
+/// The whole subtree at `path`, for the file explorer.
+#[tauri::command]
+pub async fn file_tree(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<abide_core::state::FileNode, Error> {
+    let vfs = state.vfs.read().await;
     
-    vfs.list_directory(&std::path::Path::new(&path))
-        .map_err(|e| e.to_string())
-}
\ No newline at end of file
+    vfs.tree(std::path::Path::new(&path))
+}
+#[tauri::command]
+pub async fn rename_node(
+    path: String,
+    new_name: String,
+    state: State<'_, AppState>,
+) -> Result<abide_core::state::FileNode, Error> {
+    let mut vfs = state.vfs_as(&Author::user()).await;
+    
+    vfs.rename(std::path::Path::new(&path), &new_name)
+}
+
+#[tauri::command]
+pub async fn move_node(
+    from: String,
+    to: String,
+    state: State<'_, AppState>,
+) -> Result<abide_core::state::FileNode, Error> {
+    let mut vfs = state.vfs_as(&Author::user()).await;
+    
+    vfs.move_node(std::path::Path::new(&from), std::path::Path::new(&to))
+}
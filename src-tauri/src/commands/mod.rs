@@ -1,3 +1,4 @@
+pub mod editor;
 pub mod file_operations;
 pub mod project_management;
 pub mod settings;
\ No newline at end of file
@@ -1,9 +1,9 @@
 use tauri::State;
 use serde::{Serialize, Deserialize};
-use std::sync::Arc;
-use tokio::sync::RwLock;
 
-use crate::state::VirtualFileSystem;
+use abide_core::state::workspace::{self, SaveReport};
+use abide_core::state::{AppState, Author};
+use abide_core::Error;
 
 #[derive(Serialize, Deserialize)]
 pub struct ProjectInfo {
@@ -15,14 +15,13 @@ pub struct ProjectInfo {
 #[tauri::command]
 pub async fn create_project(
     name: String,
-    vfs: State<'_, Arc<RwLock<VirtualFileSystem>>>,
-) -> Result<ProjectInfo, String> {
-    let mut vfs = vfs.write().await;
+    state: State<'_, AppState>,
+) -> Result<ProjectInfo, Error> {
+    let mut vfs = state.vfs_as(&Author::user()).await;
     
     // Create project root directory
     let project_path = format!("/{}", name);
-    vfs.create_directory(&std::path::Path::new(&project_path))
-        .map_err(|e| e.to_string())?;
+    vfs.create_directory(std::path::Path::new(&project_path))?;
     
     // Create standard project structure
     let dirs = vec![
@@ -32,22 +31,21 @@ pub async fn create_project(
     ];
     
     for dir in dirs {
-        vfs.create_directory(&std::path::Path::new(&dir))
-            .map_err(|e| e.to_string())?;
+        vfs.create_directory(std::path::Path::new(&dir))?;
     }
     
     // Create default files
     let readme_content = format!("# {}\n\nWelcome to your new ABIDE project!", name);
     vfs.create_file(
-        &std::path::Path::new(&format!("{}/README.md", project_path)),
+        std::path::Path::new(&format!("{}/README.md", project_path)),
         readme_content,
-    ).map_err(|e| e.to_string())?;
+    )?;
     
     let main_content = "// Your code starts here\n\nfunction main() {\n    console.log('Hello, ABIDE!');\n}\n\nmain();";
     vfs.create_file(
-        &std::path::Path::new(&format!("{}/src/main.js", project_path)),
+        std::path::Path::new(&format!("{}/src/main.js", project_path)),
         main_content.to_string(),
-    ).map_err(|e| e.to_string())?;
+    )?;
     
     Ok(ProjectInfo {
         name,
@@ -59,13 +57,13 @@ pub async fn create_project(
 #[tauri::command]
 pub async fn open_project(
     path: String,
-    vfs: State<'_, Arc<RwLock<VirtualFileSystem>>>,
-) -> Result<ProjectInfo, String> {
-    let vfs = vfs.read().await;
+    state: State<'_, AppState>,
+) -> Result<ProjectInfo, Error> {
+    let vfs = state.vfs.read().await;
     
-    // List directory to verify it exists
-    let files = vfs.list_directory(&std::path::Path::new(&path))
-        .map_err(|e| e.to_string())?;
+    // List directory to verify it exists, then count everything below it
+    vfs.list_directory(std::path::Path::new(&path))?;
+    let tree = vfs.tree(std::path::Path::new(&path))?;
     
     let name = std::path::Path::new(&path)
         .file_name()
@@ -76,20 +74,19 @@ pub async fn open_project(
     Ok(ProjectInfo {
         name,
         path,
-        file_count: count_files(&files),
+        file_count: count_files(&tree.children),
     })
 }
 
+/// Saves the VFS to the workspace file in the app data directory.
 #[tauri::command]
 pub async fn save_project(
-    _vfs: State<'_, Arc<RwLock<VirtualFileSystem>>>,
-) -> Result<(), String> {
-    // In a virtual file system, files are automatically saved
-    // This command exists for compatibility with traditional IDEs
-    Ok(())
+    state: State<'_, AppState>,
+) -> Result<SaveReport, Error> {
+    workspace::save_state(&state).await
 }
 
-fn count_files(nodes: &[crate::state::FileNode]) -> usize {
+fn count_files(nodes: &[abide_core::state::FileNode]) -> usize {
     nodes.iter().map(|node| {
         if node.is_directory {
             count_files(&node.children)
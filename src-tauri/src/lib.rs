@@ -0,0 +1,72 @@
+use tauri::Manager;
+use tracing::{info, error};
+
+use abide_core::state::workspace;
+use abide_core::{api, AppConfig, AppState};
+
+pub mod commands;
+
+pub fn run() {
+    // Initialize logging
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .init();
+
+    info!("Starting ABIDE - Automated Basic IDE");
+
+    tauri::Builder::default()
+        .setup(|app| {
+            info!("Initializing ABIDE application");
+
+            // A single AppState backs both the Tauri commands and the API
+            // server; clones share the same VFS, config and sessions.
+            let mut config = AppConfig::default();
+            config.mcp.prompts_dir = app.path().app_config_dir().ok().map(|dir| dir.join("prompts"));
+            let workspace_path = app.path().app_data_dir()?.join(workspace::WORKSPACE_FILE_NAME);
+            let vfs = workspace::restore(&workspace_path)?;
+            config.workspace.path = Some(workspace_path);
+            let app_state = AppState::with_config(vfs, config);
+            app.manage(app_state.clone());
+
+            // Start autosaving and the API server
+            tauri::async_runtime::spawn(async move {
+                workspace::spawn_autosave(app_state.clone());
+                if let Err(e) = api::start_server(app_state).await {
+                    error!("Failed to start API server: {}", e);
+                }
+            });
+
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            commands::file_operations::read_file,
+            commands::file_operations::write_file,
+            commands::file_operations::create_file,
+            commands::file_operations::delete_file,
+            commands::file_operations::create_directory,
+            commands::file_operations::delete_directory,
+            commands::file_operations::list_directory,
+            commands::file_operations::file_tree,
+            commands::file_operations::rename_node,
+            commands::file_operations::move_node,
+            commands::project_management::create_project,
+            commands::project_management::open_project,
+            commands::project_management::save_project,
+            commands::settings::get_settings,
+            commands::settings::update_settings,
+            commands::editor::set_editor_selection,
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Catch whatever changed since the last autosave
+            if let tauri::RunEvent::Exit = event {
+                if let Some(state) = app.try_state::<AppState>() {
+                    match tauri::async_runtime::block_on(workspace::save_state(&state)) {
+                        Ok(report) => info!("Saved workspace to {}", report.path.display()),
+                        Err(e) => error!("Failed to save workspace: {}", e),
+                    }
+                }
+            }
+        });
+}
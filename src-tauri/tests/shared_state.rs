@@ -0,0 +1,115 @@
+//! Tauri commands and the HTTP/MCP server must observe the same `AppState`.
+
+use std::sync::Arc;
+
+use abide_core::api;
+use abide_core::mcp::operations::{FileCreateParams, SettingsGetParams};
+use abide_core::mcp::{handle_mcp_request, McpRequest, McpResponse};
+use abide_core::{AppState, VirtualFileSystem};
+use abide_lib::commands::{file_operations, settings};
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use tauri::Manager;
+use tower::ServiceExt;
+
+fn setup() -> (tauri::App<tauri::test::MockRuntime>, AppState) {
+    let app = tauri::test::mock_app();
+    let state = AppState::new(VirtualFileSystem::new());
+    app.manage(state.clone());
+    (app, state)
+}
+
+#[tokio::test]
+async fn file_created_over_mcp_is_listed_by_command() {
+    let (app, state) = setup();
+
+    let response = handle_mcp_request(
+        McpRequest::FileCreate(FileCreateParams {
+            path: "/agent.js".to_string(),
+            content: "console.log('hi');".to_string(),
+            parents: false,
+        }),
+        &Arc::new(state),
+    )
+    .await;
+    assert!(matches!(response, McpResponse::Success { .. }));
+
+    let nodes = file_operations::list_directory("/".to_string(), app.state::<AppState>())
+        .await
+        .unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].name, "agent.js");
+}
+
+#[tokio::test]
+async fn file_created_by_command_is_readable_over_rest() {
+    let (app, state) = setup();
+
+    let file_id = file_operations::create_file(
+        "/ui.md".to_string(),
+        "# From the UI".to_string(),
+        None,
+        app.state::<AppState>(),
+    )
+    .await
+    .unwrap();
+
+    let response = api::router(state)
+        .oneshot(
+            Request::get(format!("/api/files/{}", file_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let file: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(file["content"], "# From the UI");
+}
+
+#[tokio::test]
+async fn file_updated_over_rest_is_read_by_command() {
+    let (app, state) = setup();
+
+    let file_id = state
+        .vfs
+        .write()
+        .await
+        .create_file(std::path::Path::new("/main.rs"), String::new())
+        .unwrap();
+
+    let response = api::router(state)
+        .oneshot(
+            Request::post(format!("/api/files/{}", file_id))
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"content":"fn main() {}"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let file = file_operations::read_file(file_id, app.state::<AppState>())
+        .await
+        .unwrap();
+    assert_eq!(file.content, "fn main() {}");
+}
+
+#[tokio::test]
+async fn settings_updated_by_command_are_served_over_mcp() {
+    let (app, state) = setup();
+
+    settings::update_settings(
+        serde_json::json!({ "editor": { "tab_size": 2 } }),
+        app.state::<AppState>(),
+    )
+    .await
+    .unwrap();
+
+    match handle_mcp_request(McpRequest::SettingsGet(SettingsGetParams {}), &Arc::new(state)).await {
+        McpResponse::Success { data } => assert_eq!(data["editor"]["tab_size"], 2),
+        other => panic!("unexpected response: {:?}", other),
+    }
+}
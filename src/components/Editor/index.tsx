@@ -2,6 +2,7 @@ import { useRef, useEffect } from 'react'
 import Editor, { Monaco } from '@monaco-editor/react'
 import { useEditorStore } from '@/store/editorStore'
 import { usePreviewStore } from '@/store/previewStore'
+import { invoke } from '@/lib/tauri'
 import type * as monaco from 'monaco-editor'
 import { X, FileText } from 'lucide-react'
 import './editor.css'
@@ -71,6 +72,21 @@ export default function CodeEditor() {
     editor.onDidChangeCursorPosition((e) => {
       setCursorPosition(e.position.lineNumber, e.position.column)
     })
+    
+    // Share the selection with the backend for MCP prompts (zero-based positions)
+    editor.onDidChangeCursorSelection((e) => {
+      const file = useEditorStore.getState().currentFile
+      const { selection } = e
+      invoke('set_editor_selection', {
+        selection: file && !selection.isEmpty()
+          ? {
+              file_id: file.id,
+              start: { line: selection.startLineNumber - 1, column: selection.startColumn - 1 },
+              end: { line: selection.endLineNumber - 1, column: selection.endColumn - 1 },
+            }
+          : null,
+      })
+    })
   }
   
   const handleEditorChange = (value: string | undefined) => {
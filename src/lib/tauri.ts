@@ -9,6 +9,43 @@
 // Check if we're running in Tauri context
 const isTauri = typeof window !== 'undefined' && window.__TAURI__ !== undefined
 
+/**
+ * Rejection value of the file system commands: `kind` is stable
+ * (`not_found`, `already_exists`, `invalid_path`, ...) and variant details
+ * such as `path` or `target` are included alongside the message.
+ */
+export interface CommandError {
+  kind: string
+  message: string
+  [detail: string]: unknown
+}
+
+/** Mock explorer contents for development */
+const MOCK_NODES = [
+  {
+    id: '1',
+    name: 'src',
+    path: '/src',
+    isDirectory: true,
+    children: [
+      {
+        id: '2',
+        name: 'main.js',
+        path: '/src/main.js',
+        isDirectory: false,
+        fileId: 'file-1'
+      }
+    ]
+  },
+  {
+    id: '3',
+    name: 'README.md',
+    path: '/README.md',
+    isDirectory: false,
+    fileId: 'file-2'
+  }
+]
+
 /**
  * Mock invoke function for development
  * Returns mock data based on the command
@@ -25,31 +62,11 @@ export async function invoke<T = unknown>(cmd: string, args?: Record<string, unk
   console.log(`[Mock] Invoking command: ${cmd}`, args)
   
   switch (cmd) {
+    case 'file_tree':
+      return { id: '0', name: '/', path: '/', isDirectory: true, children: MOCK_NODES } as T
+      
     case 'list_directory':
-      return [
-        {
-          id: '1',
-          name: 'src',
-          path: '/src',
-          isDirectory: true,
-          children: [
-            {
-              id: '2',
-              name: 'main.js',
-              path: '/src/main.js',
-              isDirectory: false,
-              fileId: 'file-1'
-            }
-          ]
-        },
-        {
-          id: '3',
-          name: 'README.md',
-          path: '/README.md',
-          isDirectory: false,
-          fileId: 'file-2'
-        }
-      ] as T
+      return MOCK_NODES.map((node) => ({ ...node, children: [] })) as T
       
     case 'read_file':
       return {
@@ -67,6 +84,7 @@ export async function invoke<T = unknown>(cmd: string, args?: Record<string, unk
     case 'write_file':
     case 'delete_file':
     case 'create_directory':
+    case 'set_editor_selection':
       return undefined as T
       
     default:
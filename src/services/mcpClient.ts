@@ -1,16 +1,27 @@
-export interface McpMessage {
-  id: string
-  type: 'request' | 'response' | 'notification'
+/**
+ * MCP client for the ABIDE backend, speaking JSON-RPC 2.0 over the `/mcp`
+ * WebSocket. Tool definitions are not duplicated here: they come from the
+ * server's `tools/list`, which derives them from the Rust operations.
+ */
+
+export const MCP_PROTOCOL_VERSION = '2025-06-18'
+
+export interface JsonRpcRequest {
+  jsonrpc: '2.0'
+  id: number
+  method: string
+  params?: Record<string, unknown>
 }
 
-export interface McpRequest extends McpMessage {
-  type: 'request'
+export interface JsonRpcNotification {
+  jsonrpc: '2.0'
   method: string
   params?: Record<string, unknown>
 }
 
-export interface McpResponse extends McpMessage {
-  type: 'response'
+export interface JsonRpcResponse {
+  jsonrpc: '2.0'
+  id: number | string | null
   result?: unknown
   error?: {
     code: number
@@ -19,228 +30,65 @@ export interface McpResponse extends McpMessage {
   }
 }
 
-export interface McpNotification extends McpMessage {
-  type: 'notification'
-  method: string
-  params?: Record<string, unknown>
-}
-
-// ABIDE-specific MCP tool definitions
-export interface AbideToolDefinition {
+/** A tool as listed by the server, with its JSON Schema input */
+export interface ToolDefinition {
   name: string
   description: string
-  inputSchema: {
-    type: 'object'
-    properties: Record<string, unknown>
-    required?: string[]
-  }
+  inputSchema: Record<string, unknown>
 }
 
-// Available ABIDE tools for MCP
-export const ABIDE_TOOLS: AbideToolDefinition[] = [
-  {
-    name: 'cursor_move_to',
-    description: 'Move cursor to a specific element or position',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        selector: { type: 'string', description: 'CSS selector for target element' },
-        position: { 
-          type: 'object',
-          properties: {
-            x: { type: 'number' },
-            y: { type: 'number' }
-          }
-        },
-        duration: { type: 'number', description: 'Animation duration in milliseconds' }
-      }
-    }
-  },
-  {
-    name: 'cursor_click',
-    description: 'Click at current cursor position or target element',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        selector: { type: 'string', description: 'CSS selector for target element (optional)' },
-        position: { 
-          type: 'object',
-          properties: {
-            x: { type: 'number' },
-            y: { type: 'number' }
-          }
-        }
-      }
-    }
-  },
-  {
-    name: 'cursor_type',
-    description: 'Type text at current cursor position or in target input field',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        text: { type: 'string', description: 'Text to type' },
-        selector: { type: 'string', description: 'CSS selector for input field (optional)' },
-        speed: { type: 'number', description: 'Typing speed in characters per minute' }
-      },
-      required: ['text']
-    }
-  },
-  {
-    name: 'file_create',
-    description: 'Create a new file in the virtual file system',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        path: { type: 'string', description: 'File path' },
-        content: { type: 'string', description: 'File content' }
-      },
-      required: ['path', 'content']
-    }
-  },
-  {
-    name: 'file_read',
-    description: 'Read content from a file',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        path: { type: 'string', description: 'File path' }
-      },
-      required: ['path']
-    }
-  },
-  {
-    name: 'file_update',
-    description: 'Update content of an existing file',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        path: { type: 'string', description: 'File path' },
-        content: { type: 'string', description: 'New file content' }
-      },
-      required: ['path', 'content']
-    }
-  },
-  {
-    name: 'file_delete',
-    description: 'Delete a file from the virtual file system',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        path: { type: 'string', description: 'File path' }
-      },
-      required: ['path']
-    }
-  },
-  {
-    name: 'directory_list',
-    description: 'List contents of a directory',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        path: { type: 'string', description: 'Directory path' }
-      },
-      required: ['path']
-    }
-  },
-  {
-    name: 'directory_create',
-    description: 'Create a new directory',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        path: { type: 'string', description: 'Directory path' }
-      },
-      required: ['path']
-    }
-  },
-  {
-    name: 'editor_open_file',
-    description: 'Open a file in the Monaco editor',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        path: { type: 'string', description: 'File path' }
-      },
-      required: ['path']
-    }
-  },
-  {
-    name: 'editor_get_selection',
-    description: 'Get current selection in the editor',
-    inputSchema: {
-      type: 'object',
-      properties: {}
-    }
-  },
-  {
-    name: 'editor_insert_text',
-    description: 'Insert text at current cursor position in editor',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        text: { type: 'string', description: 'Text to insert' }
-      },
-      required: ['text']
-    }
-  },
-  {
-    name: 'editor_replace_selection',
-    description: 'Replace selected text in editor',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        text: { type: 'string', description: 'Replacement text' }
-      },
-      required: ['text']
-    }
-  },
-  {
-    name: 'animation_typing',
-    description: 'Start a typing animation effect',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        text: { type: 'string', description: 'Text to animate' },
-        speed: { type: 'number', description: 'Animation speed' },
-        element: { type: 'string', description: 'Target element selector' }
-      },
-      required: ['text']
-    }
+export interface CallToolResult {
+  content: { type: 'text'; text: string }[]
+  structuredContent?: Record<string, unknown>
+  isError: boolean
+}
+
+/** A tool call the server ran but that failed, e.g. a missing file */
+export class McpToolError extends Error {
+  constructor(
+    public tool: string,
+    message: string,
+    public data?: unknown
+  ) {
+    super(message)
+    this.name = 'McpToolError'
   }
-]
+}
 
 class McpClient {
   private ws: WebSocket | null = null
-  private messageHandlers: Map<string, (response: McpResponse) => void> = new Map()
+  private pending: Map<number, (response: JsonRpcResponse) => void> = new Map()
   private requestId = 0
   private reconnectAttempts = 0
   private maxReconnectAttempts = 5
   private reconnectDelay = 1000
+  private tools: ToolDefinition[] | null = null
+
+  /** Called with each notification the server sends */
+  onNotification?: (notification: JsonRpcNotification) => void
 
   constructor(private url: string = 'ws://localhost:3030/mcp') {}
 
+  /** Opens the connection and runs the MCP initialize handshake. */
   async connect(): Promise<void> {
-    return new Promise((resolve, reject) => {
+    await new Promise<void>((resolve, reject) => {
       this.ws = new WebSocket(this.url)
 
       this.ws.onopen = () => {
-        console.log('Connected to MCP server')
         this.reconnectAttempts = 0
         resolve()
       }
 
       this.ws.onmessage = (event) => {
         try {
-          const message: McpMessage = JSON.parse(event.data)
-          this.handleMessage(message)
+          this.handleMessage(JSON.parse(event.data))
         } catch (error) {
           console.error('Failed to parse MCP message:', error)
         }
       }
 
       this.ws.onclose = () => {
-        console.log('Disconnected from MCP server')
+        this.tools = null
         this.attemptReconnect()
       }
 
@@ -249,47 +97,58 @@ class McpClient {
         reject(error)
       }
     })
+
+    await this.sendRequest('initialize', {
+      protocolVersion: MCP_PROTOCOL_VERSION,
+      capabilities: {},
+      clientInfo: { name: 'abide-frontend', version: '0.1.0' }
+    })
+    this.sendNotification('notifications/initialized')
   }
 
-  private handleMessage(message: McpMessage) {
-    if (message.type === 'response') {
-      const response = message as McpResponse
-      const handler = this.messageHandlers.get(response.id)
+  private handleMessage(message: JsonRpcResponse | JsonRpcNotification) {
+    if ('id' in message && typeof message.id === 'number') {
+      const handler = this.pending.get(message.id)
       if (handler) {
-        handler(response)
-        this.messageHandlers.delete(response.id)
+        handler(message)
+        this.pending.delete(message.id)
       }
-    } else if (message.type === 'notification') {
-      // Handle notifications from server
-      console.log('Received notification:', message)
+    } else if ('method' in message) {
+      // Server-initiated notifications, e.g. resource updates
+      this.onNotification?.(message as JsonRpcNotification)
     }
   }
 
   private attemptReconnect() {
     if (this.reconnectAttempts < this.maxReconnectAttempts) {
       this.reconnectAttempts++
-      console.log(`Attempting to reconnect (${this.reconnectAttempts}/${this.maxReconnectAttempts})...`)
-      
       setTimeout(() => {
         this.connect().catch(console.error)
       }, this.reconnectDelay * this.reconnectAttempts)
     }
   }
 
-  async sendRequest(method: string, params?: Record<string, unknown>): Promise<unknown> {
+  private send(message: JsonRpcRequest | JsonRpcNotification) {
     if (!this.ws || this.ws.readyState !== WebSocket.OPEN) {
       throw new Error('MCP client not connected')
     }
+    this.ws.send(JSON.stringify(message))
+  }
+
+  sendNotification(method: string, params?: Record<string, unknown>) {
+    this.send({ jsonrpc: '2.0', method, params })
+  }
 
-    const request: McpRequest = {
-      id: (++this.requestId).toString(),
-      type: 'request',
+  async sendRequest(method: string, params?: Record<string, unknown>): Promise<unknown> {
+    const request: JsonRpcRequest = {
+      jsonrpc: '2.0',
+      id: ++this.requestId,
       method,
       params
     }
 
     return new Promise((resolve, reject) => {
-      this.messageHandlers.set(request.id, (response: McpResponse) => {
+      this.pending.set(request.id, (response: JsonRpcResponse) => {
         if (response.error) {
           reject(new Error(response.error.message))
         } else {
@@ -297,96 +156,85 @@ class McpClient {
         }
       })
 
-      this.ws!.send(JSON.stringify(request))
+      try {
+        this.send(request)
+      } catch (error) {
+        this.pending.delete(request.id)
+        reject(error)
+        return
+      }
 
       // Set timeout for request
       setTimeout(() => {
-        if (this.messageHandlers.has(request.id)) {
-          this.messageHandlers.delete(request.id)
+        if (this.pending.has(request.id)) {
+          this.pending.delete(request.id)
           reject(new Error(`Request timeout: ${method}`))
         }
       }, 30000) // 30 second timeout
     })
   }
 
-  // ABIDE-specific tool methods
-  async cursorMoveTo(selector?: string, position?: { x: number; y: number }, duration?: number): Promise<void> {
-    await this.sendRequest('cursor_move_to', {
-      selector,
-      position,
-      duration
-    })
+  /** The server's tools, fetched once per connection. */
+  async listTools(): Promise<ToolDefinition[]> {
+    if (!this.tools) {
+      const result = await this.sendRequest('tools/list') as { tools: ToolDefinition[] }
+      this.tools = result.tools
+    }
+    return this.tools
   }
 
-  async cursorClick(selector?: string, position?: { x: number; y: number }): Promise<void> {
-    await this.sendRequest('cursor_click', {
-      selector,
-      position
-    })
-  }
+  /**
+   * Calls a tool and returns its result data. A failed operation rejects
+   * with a `McpToolError` carrying the error's `kind` and details.
+   */
+  async callTool<T = unknown>(name: string, args: Record<string, unknown> = {}): Promise<T> {
+    const result = await this.sendRequest('tools/call', { name, arguments: args }) as CallToolResult
+    const text = result.content[0]?.text ?? ''
 
-  async cursorType(text: string, selector?: string, speed?: number): Promise<void> {
-    await this.sendRequest('cursor_type', {
-      text,
-      selector,
-      speed
-    })
+    if (result.isError) {
+      const error = result.structuredContent?.error as { data?: unknown } | undefined
+      throw new McpToolError(name, text, error?.data)
+    }
+    return (result.structuredContent ?? JSON.parse(text)) as T
   }
 
+  // Shorthands for the tools the UI uses
   async fileCreate(path: string, content: string): Promise<string> {
-    return await this.sendRequest('file_create', { path, content }) as string
+    const { id } = await this.callTool<{ id: string }>('file_create', { path, content })
+    return id
   }
 
   async fileRead(path: string): Promise<string> {
-    return await this.sendRequest('file_read', { path }) as string
+    const { content } = await this.callTool<{ content: string }>('file_read', { path })
+    return content
   }
 
-  async fileUpdate(path: string, content: string): Promise<void> {
-    await this.sendRequest('file_update', { path, content })
+  async fileUpdate(path: string, content: string): Promise<number> {
+    const { version } = await this.callTool<{ version: number }>('file_update', { path, content })
+    return version
   }
 
   async fileDelete(path: string): Promise<void> {
-    await this.sendRequest('file_delete', { path })
+    await this.callTool('file_delete', { path })
   }
 
   async directoryList(path: string): Promise<unknown[]> {
-    return await this.sendRequest('directory_list', { path }) as unknown[]
+    return await this.callTool<unknown[]>('directory_list', { path })
   }
 
   async directoryCreate(path: string): Promise<string> {
-    return await this.sendRequest('directory_create', { path }) as string
-  }
-
-  async editorOpenFile(path: string): Promise<void> {
-    await this.sendRequest('editor_open_file', { path })
-  }
-
-  async editorGetSelection(): Promise<unknown> {
-    return await this.sendRequest('editor_get_selection')
-  }
-
-  async editorInsertText(text: string): Promise<void> {
-    await this.sendRequest('editor_insert_text', { text })
-  }
-
-  async editorReplaceSelection(text: string): Promise<void> {
-    await this.sendRequest('editor_replace_selection', { text })
-  }
-
-  async startTypingAnimation(text: string, speed?: number, element?: string): Promise<void> {
-    await this.sendRequest('animation_typing', {
-      text,
-      speed,
-      element
-    })
+    const { id } = await this.callTool<{ id: string }>('directory_create', { path })
+    return id
   }
 
   disconnect() {
     if (this.ws) {
+      this.ws.onclose = null
       this.ws.close()
       this.ws = null
     }
-    this.messageHandlers.clear()
+    this.pending.clear()
+    this.tools = null
   }
 
   isConnected(): boolean {
@@ -397,4 +245,4 @@ class McpClient {
 // Singleton instance
 export const mcpClient = new McpClient()
 
-export default McpClient
\ No newline at end of file
+export default McpClient
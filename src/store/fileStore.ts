@@ -35,8 +35,8 @@ export const useFileStore = create<FileState>()(
       
       loadInitialFiles: async () => {
         try {
-          const nodes = await invoke<FileNode[]>('list_directory', { path: '/' })
-          set({ fileTree: nodes })
+          const root = await invoke<FileNode>('file_tree', { path: '/' })
+          set({ fileTree: root.children ?? [] })
         } catch (error) {
           console.error('Failed to load files:', error)
         }
@@ -90,8 +90,8 @@ export const useFileStore = create<FileState>()(
       },
       
       refreshFileTree: async () => {
-        const nodes = await invoke<FileNode[]>('list_directory', { path: '/' })
-        set({ fileTree: nodes })
+        const root = await invoke<FileNode>('file_tree', { path: '/' })
+        set({ fileTree: root.children ?? [] })
       },
     }),
     {